@@ -4,6 +4,7 @@
 //! Здесь собраны основные сущности
 
 use serde::Deserialize;
+use std::collections::HashMap;
 
 
 #[derive(Debug, Deserialize, Clone)]
@@ -11,28 +12,570 @@ pub struct Criterion {
     pub id: String,
     pub weight: Option<f64>, // для многокритериальных задач
     pub maximize: Option<bool>, // true если критерий максимизируемый
+    #[serde(default)]
+    pub children: Vec<Criterion>, // подкритерии (узел дерева ценностей); значения задаются только для листьев
+    pub utility: Option<UtilityFunction>, // однокритериальная функция полезности (MAUT)
+    pub scale: Option<Scale>, // качественная шкала: значения альтернатив задаются метками
 }
 
 
+/// Качественная шкала критерия.
+/// Порядковая — список меток от худшей к лучшей (переводятся в 1, 2, …);
+/// лингвистическая — соответствие метки числу, интервалу или треугольному нечёткому числу.
 #[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Scale {
+    Ordinal(Vec<String>),
+    Linguistic(HashMap<String, CellValue>),
+}
+
+
+/// Однокритериальная функция полезности u(x) ∈ [0, 1].
+/// Для linear и exponential границы min/max по умолчанию берутся из данных,
+/// а направление — из `Criterion.maximize`.
+/// cara, log и crra заданы в денежных единицах и нормируются на диапазон данных.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UtilityFunction {
+    Linear { min: Option<f64>, max: Option<f64> },
+    /// u = (1 − e^{−c·z}) / (1 − e^{−c}), z — нормированное значение; c > 0 — неприятие риска, c < 0 — склонность
+    Exponential { risk: f64, min: Option<f64>, max: Option<f64> },
+    /// Кусочно-линейная по выявленным точкам [x, u]
+    Piecewise { points: Vec<(f64, f64)> },
+    /// Ступенчатая: u равна значению последнего порога [x, u] с x ≤ значения (0 ниже первого порога)
+    Step { thresholds: Vec<(f64, f64)> },
+    /// Постоянное абсолютное неприятие риска: u = (1 − e^{−a·x}) / a (при a = 0 — линейная)
+    Cara { a: f64 },
+    /// Логарифмическая: u = ln(x + shift), shift — начальный капитал (x + shift > 0)
+    Log { shift: Option<f64> },
+    /// Постоянное относительное неприятие риска: u = ((x + shift)^{1−γ} − 1) / (1 − γ), при γ = 1 — ln
+    Crra { gamma: f64, shift: Option<f64> },
+}
+
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(from = "RawAlternative")]
 pub struct Alternative {
     pub id: String,
-    pub values: Vec<f64>, // значения по критериям в порядке criteria
+    pub values: Vec<f64>, // значения по критериям в порядке criteria (для интервалов и нечётких чисел — центр)
+    pub cells: Vec<CellValue>, // исходные значения в том виде, как они заданы во входном файле
+    pub labels: Vec<Option<String>>, // метки качественных шкал (заменяются на числа при чтении задачи)
+}
+
+
+/// Значение в матрице решений: число, интервал [a, b] или треугольное нечёткое число [l, m, u]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum CellValue {
+    Crisp(f64),
+    Interval([f64; 2]),
+    Triangular([f64; 3]),
+}
+
+
+impl CellValue {
+    /// Чёткий представитель: середина интервала или центр тяжести треугольного числа
+    pub fn representative(&self) -> f64 {
+        match self {
+            CellValue::Crisp(x) => *x,
+            CellValue::Interval([a, b]) => (a + b) / 2.0,
+            CellValue::Triangular([l, m, u]) => (l + m + u) / 3.0,
+        }
+    }
+}
+
+
+impl Alternative {
+    /// Номера критериев, значения по которым не заданы (null во входном файле)
+    pub fn missing(&self) -> Vec<usize> {
+        self.values.iter().enumerate().filter(|(_, v)| v.is_nan()).map(|(j, _)| j).collect()
+    }
+}
+
+
+impl std::fmt::Display for CellValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellValue::Crisp(x) => write!(f, "{}", x),
+            CellValue::Interval([a, b]) => write!(f, "[{}, {}]", a, b),
+            CellValue::Triangular([l, m, u]) => write!(f, "[{}, {}, {}]", l, m, u),
+        }
+    }
+}
+
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCell {
+    Value(CellValue),
+    Label(String),
+    Missing, // null — значение неизвестно
+}
+
+
+#[derive(Deserialize)]
+struct RawAlternative {
+    id: String,
+    values: Vec<RawCell>,
+}
+
+
+impl From<RawAlternative> for Alternative {
+    fn from(raw: RawAlternative) -> Self {
+        // пропуск хранится как NaN; на месте метки NaN стоит до разбора шкалы
+        let cells: Vec<CellValue> = raw.values.iter().map(|c| match c {
+            RawCell::Value(v) => *v,
+            RawCell::Label(_) | RawCell::Missing => CellValue::Crisp(f64::NAN),
+        }).collect();
+        Alternative {
+            id: raw.id,
+            values: cells.iter().map(|c| c.representative()).collect(),
+            cells,
+            labels: raw.values.into_iter().map(|c| match c {
+                RawCell::Value(_) | RawCell::Missing => None,
+                RawCell::Label(l) => Some(l),
+            }).collect(),
+        }
+    }
+}
+
+
+/// Нечёткая мера (ёмкость): полностью, по всем подмножествам критериев,
+/// либо 2-аддитивная модель через индексы Шепли и попарные взаимодействия.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Capacity {
+    /// μ(S) для непустых подмножеств; μ(всех критериев) = 1 можно не указывать
+    Full { subsets: Vec<CapacityValue> },
+    TwoAdditive {
+        shapley: HashMap<String, f64>,
+        #[serde(default)]
+        interactions: Vec<CapacityValue>,
+    },
+}
+
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CapacityValue {
+    pub criteria: Vec<String>,
+    pub value: f64,
+}
+
+
+/// Модель неточного прогноза (эксперимента): matrix[k][j] = P(сигнал k | состояние j)
+#[derive(Debug, Deserialize, Clone)]
+pub struct Likelihood {
+    pub signals: Vec<String>,
+    pub matrix: Vec<Vec<f64>>,
+}
+
+
+impl Likelihood {
+    /// Проверка размеров (строка на сигнал, столбец на состояние) и нормировки по каждому состоянию
+    pub fn validate(&self, states: usize) -> anyhow::Result<()> {
+        if self.matrix.len() != self.signals.len() || self.matrix.iter().any(|row| row.len() != states) {
+            anyhow::bail!("Матрица правдоподобия должна иметь строку на каждый сигнал и столбец на каждое состояние");
+        }
+        if self.matrix.iter().flatten().any(|p| *p < 0.0) {
+            anyhow::bail!("Вероятности сигналов должны быть неотрицательными");
+        }
+        if let Some(j) = (0..states).find(|&j| (self.matrix.iter().map(|row| row[j]).sum::<f64>() - 1.0).abs() > 1e-6) {
+            anyhow::bail!("Вероятности сигналов при состоянии №{} должны давать в сумме 1", j + 1);
+        }
+        Ok(())
+    }
+
+    /// Номер сигнала по названию
+    pub fn signal_index(&self, name: &str) -> anyhow::Result<usize> {
+        self.signals.iter().position(|s| s == name)
+            .ok_or_else(|| anyhow::anyhow!("Неизвестный исход наблюдения: {}", name))
+    }
+}
+
+
+/// Частичные сведения о вероятностях состояний: по ним вероятности оцениваются
+/// по принципу максимума энтропии (интервалы из `state_probabilities` учитываются как границы)
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProbabilityConstraints {
+    /// Известные вероятности отдельных состояний: название состояния → вероятность
+    #[serde(default)]
+    pub fixed: HashMap<String, f64>,
+    /// Числовые значения состояний (например, спрос); по умолчанию — число в названии состояния
+    pub state_values: Option<Vec<f64>>,
+    /// Известное ожидание значения состояния
+    pub mean: Option<f64>,
+    /// Цепочки состояний в порядке невозрастания вероятности: [a, b, c] — P(a) ≥ P(b) ≥ P(c)
+    #[serde(default)]
+    pub order: Vec<Vec<String>>,
 }
 
 
+/// Смысл значений матрицы в задачах неопределённости и риска:
+/// выплаты (больше — лучше) или потери/затраты (меньше — лучше)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    #[default]
+    Gain,
+    Loss,
+}
+
+
+impl Orientation {
+    /// "gain" | "loss"
+    pub fn from_name(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "gain" => Ok(Orientation::Gain),
+            "loss" => Ok(Orientation::Loss),
+            _ => anyhow::bail!("Unknown matrix orientation: {}", name),
+        }
+    }
+
+    /// +1 для выплат, −1 для потерь: sign·x переводит значение в «выплату»
+    pub fn sign(&self) -> f64 {
+        match self {
+            Orientation::Gain => 1.0,
+            Orientation::Loss => -1.0,
+        }
+    }
+
+    /// Лучшее из значений: максимум выплат или минимум потерь
+    pub fn best(&self, values: &[f64]) -> f64 {
+        match self {
+            Orientation::Gain => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            Orientation::Loss => values.iter().cloned().fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    /// Худшее из значений: минимум выплат или максимум потерь
+    pub fn worst(&self, values: &[f64]) -> f64 {
+        match self {
+            Orientation::Gain => values.iter().cloned().fold(f64::INFINITY, f64::min),
+            Orientation::Loss => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+
+/// Задача в том виде, как она записана во входном файле; разбирается в `ProblemSpec`
+#[derive(Deserialize)]
+struct RawProblemSpec {
+    alternatives: Vec<Alternative>,
+    criteria: Vec<Criterion>,
+    // числа или интервалы [нижняя, верхняя]
+    state_probabilities: Option<Vec<CellValue>>,
+    probability_constraints: Option<ProbabilityConstraints>,
+    capacity: Option<Capacity>,
+    order_weights: Option<Vec<f64>>,
+    likelihood: Option<Likelihood>,
+    observations: Option<Vec<String>>,
+    utility: Option<UtilityFunction>,
+    #[serde(default)]
+    orientation: Orientation,
+}
+
+
+/// При десериализации задача сразу приводится к рабочему виду: дерево критериев разворачивается,
+/// метки шкал заменяются числами, вероятности состояний разбираются (см. `TryFrom<RawProblemSpec>`)
 #[derive(Debug, Deserialize, Clone)]
+#[serde(try_from = "RawProblemSpec")]
 pub struct ProblemSpec {
     pub alternatives: Vec<Alternative>,
     pub criteria: Vec<Criterion>,
     // Для риск-задач можно указать вероятности состояния (по столбцам альтернативы могут быть векторами ожиданий)
     pub state_probabilities: Option<Vec<f64>>,
+    // Неточные вероятности: границы [нижняя, верхняя] по состояниям, если хотя бы одна задана интервалом
+    pub probability_bounds: Option<Vec<(f64, f64)>>,
+    // Частичные сведения о вероятностях: если числа не заданы, вероятности оцениваются по максимуму энтропии
+    pub probability_constraints: Option<ProbabilityConstraints>,
+    // Нечёткая мера (ёмкость) на множестве критериев для интеграла Шоке
+    pub capacity: Option<Capacity>,
+    // Порядковые веса OWA: w_1 относится к наибольшему значению, w_n — к наименьшему
+    pub order_weights: Option<Vec<f64>>,
+    // Правдоподобие сигналов прогноза по состояниям (для ценности выборочной информации)
+    pub likelihood: Option<Likelihood>,
+    // Наблюдённые исходы (названия сигналов из likelihood) для последовательного байесовского пересчёта
+    pub observations: Option<Vec<String>>,
+    // Функция полезности выплат для критерия ожидаемой полезности (по умолчанию линейная)
+    pub utility: Option<UtilityFunction>,
+    // Значения матрицы — выплаты (gain, по умолчанию) или потери (loss) для критериев неопределённости и риска
+    pub orientation: Orientation,
+    // Исходное дерево критериев, если они вложенные (в criteria после разбора остаются только листья)
+    pub hierarchy: Option<Vec<Criterion>>,
+    // Пояснения о подготовке данных (обработка пропусков и т.п.), попадают в отчёт
+    pub notes: Vec<String>,
+}
+
+
+impl TryFrom<RawProblemSpec> for ProblemSpec {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawProblemSpec) -> anyhow::Result<Self> {
+        let mut spec = ProblemSpec {
+            alternatives: raw.alternatives,
+            criteria: raw.criteria,
+            state_probabilities: None,
+            probability_bounds: None,
+            probability_constraints: raw.probability_constraints,
+            capacity: raw.capacity,
+            order_weights: raw.order_weights,
+            likelihood: raw.likelihood,
+            observations: raw.observations,
+            utility: raw.utility,
+            orientation: raw.orientation,
+            hierarchy: None,
+            notes: Vec::new(),
+        };
+        spec.flatten_hierarchy();
+        spec.resolve_labels()?;
+        if let Some(cells) = &raw.state_probabilities {
+            spec.resolve_probabilities(cells)?;
+        }
+        Ok(spec)
+    }
+}
+
+
+impl ProblemSpec {
+    /// Разворачивает дерево критериев: в `criteria` остаются листья (в порядке обхода, т.е. в порядке
+    /// столбцов `values`) с глобальными весами — произведениями нормированных локальных весов по пути.
+    /// Плоский список критериев не меняется.
+    fn flatten_hierarchy(&mut self) {
+        if self.criteria.iter().all(|c| c.children.is_empty()) {
+            return;
+        }
+        let tree = std::mem::take(&mut self.criteria);
+        let mut leaves = Vec::new();
+        collect_leaves(&tree, 1.0, &mut leaves);
+        self.criteria = leaves;
+        self.hierarchy = Some(tree);
+    }
+
+    /// Разбирает вероятности состояний из входного файла: если все заданы числами — в `state_probabilities`,
+    /// если есть интервалы — в `probability_bounds` (числа становятся вырожденными интервалами).
+    /// Границы должны допускать хотя бы одно распределение: Σ нижних ≤ 1 ≤ Σ верхних.
+    fn resolve_probabilities(&mut self, cells: &[CellValue]) -> anyhow::Result<()> {
+        let bounds: Vec<(f64, f64)> = cells.iter()
+            .map(|cell| match cell {
+                CellValue::Crisp(p) => Ok((*p, *p)),
+                CellValue::Interval([lo, hi]) => Ok((*lo, *hi)),
+                CellValue::Triangular(_) => anyhow::bail!("Вероятность состояния задаётся числом или интервалом [нижняя, верхняя]: {}", cell),
+            })
+            .collect::<anyhow::Result<_>>()?;
+        if bounds.iter().all(|(lo, hi)| lo == hi) {
+            self.state_probabilities = Some(bounds.into_iter().map(|(p, _)| p).collect());
+            return Ok(());
+        }
+        if let Some((lo, hi)) = bounds.iter().find(|(lo, hi)| !(0.0..=1.0).contains(lo) || !(0.0..=1.0).contains(hi) || lo > hi) {
+            anyhow::bail!("Интервал вероятности [{}, {}] должен лежать в [0, 1] и иметь нижнюю границу не больше верхней", lo, hi);
+        }
+        let lower: f64 = bounds.iter().map(|(lo, _)| lo).sum();
+        let upper: f64 = bounds.iter().map(|(_, hi)| hi).sum();
+        if lower > 1.0 + 1e-9 || upper < 1.0 - 1e-9 {
+            anyhow::bail!("Интервалы вероятностей несовместны: сумма нижних границ {}, сумма верхних {}", lower, upper);
+        }
+        self.probability_bounds = Some(bounds);
+        Ok(())
+    }
+
+    /// Заменяет метки качественных шкал числами (или нечёткими числами) по `Criterion.scale`
+    fn resolve_labels(&mut self) -> anyhow::Result<()> {
+        for alt in &mut self.alternatives {
+            for (j, label) in alt.labels.iter().enumerate() {
+                let Some(label) = label else { continue };
+                let crit = self.criteria.get(j)
+                    .ok_or_else(|| anyhow::anyhow!("У альтернативы {} больше значений, чем критериев", alt.id))?;
+                let value = match &crit.scale {
+                    Some(Scale::Ordinal(levels)) => levels.iter().position(|l| l == label)
+                        .map(|k| CellValue::Crisp((k + 1) as f64)),
+                    Some(Scale::Linguistic(map)) => map.get(label).copied(),
+                    None => anyhow::bail!("Критерий {} не имеет шкалы, но для {} задана метка \"{}\"", crit.id, alt.id, label),
+                }.ok_or_else(|| anyhow::anyhow!("Метка \"{}\" отсутствует в шкале критерия {}", label, crit.id))?;
+                alt.cells[j] = value;
+                alt.values[j] = value.representative();
+            }
+        }
+        Ok(())
+    }
+
+    /// Исходные метки качественных шкал по альтернативам — для отчёта
+    pub fn label_notes(&self) -> Vec<String> {
+        self.alternatives
+            .iter()
+            .filter(|alt| alt.labels.iter().any(|l| l.is_some()))
+            .map(|alt| {
+                let parts: Vec<String> = alt.labels.iter().zip(self.criteria.iter()).zip(alt.cells.iter())
+                    .filter_map(|((label, crit), v)| label.as_ref().map(|l| format!("{} = {} → {}", crit.id, l, v)))
+                    .collect();
+                format!("{}: {}", alt.id, parts.join(", "))
+            })
+            .collect()
+    }
+
+    /// Названия состояний (столбцов): id критериев, если они заданы, иначе S1, S2, ...
+    pub fn state_names(&self, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|j| self.criteria.get(j).map_or_else(|| format!("S{}", j + 1), |c| c.id.clone()))
+            .collect()
+    }
+
+    /// Числовые значения состояний (например, спрос): `probability_constraints.state_values`
+    /// или числа в названиях состояний («Спрос 150» → 150); None, если их нет
+    pub fn state_values(&self, count: usize) -> Option<Vec<f64>> {
+        if let Some(values) = self.probability_constraints.as_ref().and_then(|c| c.state_values.clone()) {
+            return Some(values);
+        }
+        self.state_names(count).iter()
+            .map(|s| s.split_whitespace().rev().find_map(|t| t.parse::<f64>().ok()))
+            .collect()
+    }
+
+    /// Ветви дерева критериев: id узла-подцели и номера столбцов его листьев.
+    /// Для плоского списка критериев пусто.
+    pub fn branches(&self) -> Vec<(String, Vec<usize>)> {
+        let mut out = Vec::new();
+        if let Some(tree) = &self.hierarchy {
+            collect_branches(tree, &mut 0, &mut out);
+        }
+        out
+    }
+
+    /// Записывает веса (например, полученные BWM или AHP) в `Criterion.weight` по id критерия
+    pub fn set_weights(&mut self, weights: &[(String, f64)]) -> anyhow::Result<()> {
+        for (id, w) in weights {
+            let crit = self.criteria.iter_mut().find(|c| &c.id == id)
+                .ok_or_else(|| anyhow::anyhow!("Критерий {} отсутствует в задаче", id))?;
+            crit.weight = Some(*w);
+        }
+        Ok(())
+    }
+}
+
+
+/// Нормированные локальные веса одного уровня дерева (по умолчанию равные)
+fn level_weights(nodes: &[Criterion]) -> Vec<f64> {
+    let raw: Vec<f64> = nodes.iter().map(|c| c.weight.unwrap_or(1.0)).collect();
+    let total: f64 = raw.iter().sum();
+    raw.into_iter().map(|w| w / total).collect()
+}
+
+
+fn collect_leaves(nodes: &[Criterion], parent_weight: f64, out: &mut Vec<Criterion>) {
+    for (node, w) in nodes.iter().zip(level_weights(nodes)) {
+        if node.children.is_empty() {
+            out.push(Criterion { weight: Some(parent_weight * w), ..node.clone() });
+        } else {
+            collect_leaves(&node.children, parent_weight * w, out);
+        }
+    }
+}
+
+
+/// Возвращает листья уровня; ветви дописываются в `out` в прямом порядке
+fn collect_branches(nodes: &[Criterion], next_leaf: &mut usize, out: &mut Vec<(String, Vec<usize>)>) -> Vec<usize> {
+    let mut leaves = Vec::new();
+    for node in nodes {
+        if node.children.is_empty() {
+            leaves.push(*next_leaf);
+            *next_leaf += 1;
+        } else {
+            let pos = out.len();
+            out.push((node.id.clone(), Vec::new()));
+            let branch = collect_branches(&node.children, next_leaf, out);
+            leaves.extend(branch.iter().copied());
+            out[pos].1 = branch;
+        }
+    }
+    leaves
+}
+
+
+/// Дополнительная таблица результата: строки — альтернативы, столбцы — подцели, критерии и т.п.
+#[derive(Debug, Clone)]
+pub struct ResultTable {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<(String, Vec<f64>)>,
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct DecisionResult {
     pub chosen: Vec<String>,
     pub scores: Vec<(String, f64)>,
     pub method: String,
-}
\ No newline at end of file
+    pub weights: Option<Vec<(String, f64)>>, // веса критериев, с которыми получен результат
+    pub tables: Vec<ResultTable>,
+    pub notes: Vec<String>, // пояснения к результату (исходные метки шкал и т.п.)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> ProblemSpec {
+        serde_json::from_str(r#"{
+            "criteria": [
+                {"id": "X", "weight": 2, "children": [
+                    {"id": "x1"},
+                    {"id": "x2", "children": [{"id": "x21", "weight": 1}, {"id": "x22", "weight": 3}]}
+                ]},
+                {"id": "y", "weight": 2}
+            ],
+            "alternatives": [{"id": "A", "values": [1, 2, 3, 4]}]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn flatten_hierarchy_multiplies_weights_along_path() {
+        let spec = tree();
+        let leaves: Vec<(&str, f64)> = spec.criteria.iter().map(|c| (c.id.as_str(), c.weight.unwrap())).collect();
+        // X и y — по 1/2; x1 и x2 — по 1/2 внутри X; x21 : x22 = 1 : 3 внутри x2
+        let expected = [("x1", 0.25), ("x21", 0.0625), ("x22", 0.1875), ("y", 0.5)];
+        assert_eq!(leaves.len(), expected.len());
+        for ((id, w), (expected_id, expected_w)) in leaves.iter().zip(expected) {
+            assert_eq!(*id, expected_id);
+            assert!((w - expected_w).abs() < 1e-12);
+        }
+        assert!(spec.hierarchy.is_some());
+    }
+
+    #[test]
+    fn branches_list_leaf_columns() {
+        let spec = tree();
+        assert_eq!(spec.branches(), vec![("X".to_string(), vec![0, 1, 2]), ("x2".to_string(), vec![1, 2])]);
+
+        let flat: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{"id": "a"}, {"id": "b"}],
+            "alternatives": [{"id": "A", "values": [1, 2]}]
+        }"#).unwrap();
+        assert!(flat.branches().is_empty());
+        assert!(flat.hierarchy.is_none());
+    }
+    #[test]
+    fn deserialization_resolves_state_probabilities() {
+        let crisp: ProblemSpec = serde_json::from_str(r#"{
+            "state_probabilities": [0.25, 0.75],
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [{"id": "A", "values": [1, 2]}]
+        }"#).unwrap();
+        assert_eq!(crisp.state_probabilities, Some(vec![0.25, 0.75]));
+        assert!(crisp.probability_bounds.is_none());
+
+        let interval: ProblemSpec = serde_json::from_str(r#"{
+            "state_probabilities": [[0.2, 0.6], 0.4],
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [{"id": "A", "values": [1, 2]}]
+        }"#).unwrap();
+        assert!(interval.state_probabilities.is_none());
+        assert_eq!(interval.probability_bounds, Some(vec![(0.2, 0.6), (0.4, 0.4)]));
+
+        let err = serde_json::from_str::<ProblemSpec>(r#"{
+            "state_probabilities": [[0.7, 0.9], [0.5, 0.6]],
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [{"id": "A", "values": [1, 2]}]
+        }"#).unwrap_err();
+        assert!(err.to_string().contains("несовместны"));
+    }
+}
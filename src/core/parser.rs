@@ -10,6 +10,12 @@ use std::fs;
 
 pub fn read_spec(path: &str) -> Result<ProblemSpec> {
     let s = fs::read_to_string(path)?;
-    let spec: ProblemSpec = serde_json::from_str(&s)?;
-    Ok(spec)
+    parse_spec(&s)
+}
+
+
+/// Разбор задачи из JSON-строки (например, сгенерированной в памяти);
+/// дерево критериев, метки шкал и вероятности разбираются при десериализации `ProblemSpec`
+pub fn parse_spec(s: &str) -> Result<ProblemSpec> {
+    Ok(serde_json::from_str(s)?)
 }
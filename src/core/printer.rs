@@ -3,17 +3,79 @@
 // -----------------------------
 //! Здесь все что касается Output
 
-use crate::core::base::DecisionResult;
+use crate::core::base::{DecisionResult, ResultTable};
 
 
 pub fn print_result(res: &DecisionResult) {
     println!("Выбран метод: {}", res.method);
+    if let Some(weights) = &res.weights {
+        print_weights("Веса критериев", weights);
+    }
     println!("Значения:");
     for (id, score) in &res.scores {
         println!(" {} -> {:.4}", id, score);
     }
+    for table in &res.tables {
+        print_table(table);
+    }
+    print_notes(&res.notes);
     println!("Выбранная альтернатива(ы):");
     for id in &res.chosen {
         println!(" {}", id);
     }
 }
+
+
+pub fn print_table(table: &ResultTable) {
+    println!("{}:", table.title);
+    let label_width = table.rows.iter().map(|(id, _)| id.chars().count()).max().unwrap_or(0);
+    // ширина столбца — по заголовку и самому длинному значению, но не меньше 10
+    let widths: Vec<usize> = table.columns.iter().enumerate()
+        .map(|(j, c)| table.rows.iter()
+            .filter_map(|(_, values)| values.get(j))
+            .map(|v| format!("{:.4}", clean(*v)).len())
+            .fold(c.chars().count().max(10), usize::max))
+        .collect();
+
+    let mut header = format!(" {:<w$}", "", w = label_width);
+    for (col, w) in table.columns.iter().zip(widths.iter()) {
+        header.push_str(&format!(" | {:>w$}", col, w = w));
+    }
+    println!("{}", header);
+    for (id, values) in &table.rows {
+        let mut line = format!(" {:<w$}", id, w = label_width);
+        for (v, w) in values.iter().zip(widths.iter()) {
+            // неопределённое значение (NaN) показываем прочерком
+            if v.is_nan() {
+                line.push_str(&format!(" | {:>w$}", "—", w = w));
+            } else {
+                line.push_str(&format!(" | {:>w$.4}", clean(*v), w = w));
+            }
+        }
+        println!("{}", line);
+    }
+}
+
+
+/// Погрешности округления не должны печататься как -0.0000
+fn clean(v: f64) -> f64 {
+    if v.abs() < 1e-12 { 0.0 } else { v }
+}
+
+
+pub fn print_weights(title: &str, weights: &[(String, f64)]) {
+    println!("{}:", title);
+    for (id, w) in weights {
+        println!(" {} -> {:.4}", id, w);
+    }
+}
+
+
+pub fn print_notes(notes: &[String]) {
+    if !notes.is_empty() {
+        println!("Примечания:");
+        for note in notes {
+            println!(" {}", note);
+        }
+    }
+}
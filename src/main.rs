@@ -5,7 +5,8 @@ mod core;
 mod problems;
 mod tools;
 
-use core::printer::print_result;
+use core::base::{DecisionResult, Orientation, ProblemSpec};
+use core::printer::{print_notes, print_result, print_table, print_weights};
 use problems::{
     deterministic::DeterministicSolver, 
     multicriteria::WeightedSumSolver, 
@@ -13,18 +14,119 @@ use problems::{
     clustering::ClusteringSolver,
     decision_rules::DecisionRulesSolver,
     bayes_rules::ProbabilisticRulesSolver,
+    bayes_update::BayesUpdateSolver,
 };
 
 use crate::problems::ahp::AhpSolver;
+use crate::problems::anp::AnpSolver;
+use crate::problems::bwm::BwmSolver;
+use crate::problems::choquet::ChoquetSolver;
+use crate::problems::criteria_consensus::CriteriaConsensusSolver;
+use crate::problems::decision_tree::{DecisionTreeSolver, TreeMode};
+use crate::problems::expected_utility::ExpectedUtilitySolver;
+use crate::problems::fuzzy::{FuzzySolver, UncertaintyMode};
+use crate::problems::history_estimation::{estimate_probabilities, history_states, read_history, EstimationMethod};
+use crate::problems::imprecise_probabilities::ImpreciseProbabilitySolver;
+use crate::problems::max_entropy::{apply_max_entropy, max_entropy};
+use crate::problems::maut::{Aggregation, MautSolver};
+use crate::problems::stochastic_dominance::{prefilter, DominanceOrder, StochasticDominanceSolver};
+use crate::problems::missing::{apply_missing_policy, ensure_complete, MissingPolicy};
+use crate::problems::parameter_sweep::ParameterSweepSolver;
+use crate::problems::probability_sensitivity::ProbabilitySensitivitySolver;
+use crate::problems::weighting::{combine_weights, objective_weights, WeightingMethod};
+use crate::tools::risk_builder::NewsvendorConfig;
 
 #[derive(Parser)]
 #[command(author, version, about = "Decision support template", long_about = None)]
 struct Cli {
-    /// Путь к JSON-файлу с описанием задачи
+    /// Путь к JSON-файлу с описанием задачи (для riskbuild — конфигурация генератора)
     input: Option<String>,
-    /// Метод: deterministic | multicriteria | risk | clustering
+    /// Метод: deterministic | multicriteria | risk | information | utility | decision | probabilistic | bayesian | sweep | compare | sensitivity | imprecise | maxent | history | anp | bwm | weights | maut | choquet | interval | fuzzy | tree | dominance | clustering
     #[arg(short, long)]
     method: Option<String>,
+    /// Критерий для методов decision и probabilistic (wald, hurwicz, bayes, ...)
+    /// или способ расчёта весов для weights (entropy, critic, stddev, mean, roc, rank-sum),
+    /// или способ агрегации для maut (additive, multiplicative),
+    /// или метод для interval и fuzzy (weighted-sum, topsis),
+    /// или режим свёртки дерева для tree (ev, utility),
+    /// или порядок стохастического доминирования для dominance (fsd, ssd, tsd),
+    /// или критерий с параметром для sweep (hurwicz, hurwicz-regret, ferstner, hodge-lehman),
+    /// или критерий при интервальных вероятностях для imprecise
+    /// (gamma-maximin, gamma-maximax, interval-dominance, e-admissibility),
+    /// или способ оценки вероятностей для history и riskbuild (frequency, laplace, poisson, normal, negative-binomial)
+    #[arg(short, long)]
+    criterion: Option<String>,
+    /// Параметр критерия (α Гурвица и Гурвица на сожалениях, λ Ферстнера и Ходжа-Лемана,
+    /// α сглаживания Лапласа для history)
+    #[arg(short, long)]
+    param: Option<f64>,
+    /// Файл задачи (ProblemSpec), к которому применяются найденные веса критериев
+    /// или оценённые по истории вероятности состояний
+    #[arg(long)]
+    spec: Option<String>,
+    /// Порядок важности критериев через запятую (для весов roc и rank-sum)
+    #[arg(long, value_delimiter = ',')]
+    order: Option<Vec<String>>,
+    /// Объединить объективные веса с заданными в задаче (линейно при --param α, иначе мультипликативно)
+    #[arg(long)]
+    combine: bool,
+    /// Политика для пропущенных значений (null): exclude-alternative | exclude-criterion |
+    /// impute-mean | impute-median | impute-worst | impute-best | renormalize (только multicriteria и bwm)
+    #[arg(long)]
+    missing: Option<String>,
+    /// Уровни доверия для VaR и CVaR через запятую (для risk)
+    #[arg(long, value_delimiter = ',', default_value = "0.9,0.95")]
+    levels: Vec<f64>,
+    /// Наблюдённые исходы через запятую (для bayesian; заменяют observations из задачи)
+    #[arg(long, value_delimiter = ',')]
+    observe: Option<Vec<String>>,
+    /// Файл для сохранения решённого дерева в формате Graphviz DOT (для tree)
+    #[arg(long)]
+    dot: Option<String>,
+    /// Предварительно исключить стохастически доминируемые альтернативы: fsd | ssd | tsd
+    /// (для probabilistic, bayesian, compare и utility)
+    #[arg(long)]
+    dominance: Option<String>,
+    /// Смысл значений матрицы: gain (выплаты) | loss (потери); заменяет orientation из задачи
+    /// (для decision, probabilistic, bayesian, sweep, compare, sensitivity, imprecise и dominance)
+    #[arg(long)]
+    orientation: Option<String>,
+    /// Уровни спроса через запятую (для riskbuild; заменяют demands из конфигурации)
+    #[arg(long, value_delimiter = ',')]
+    demands: Option<Vec<f64>>,
+    /// Объёмы заказа через запятую (для riskbuild; по умолчанию — уровни спроса)
+    #[arg(long, value_delimiter = ',')]
+    quantities: Option<Vec<f64>>,
+    /// Вероятности уровней спроса через запятую (для riskbuild; заменяют history)
+    #[arg(long, value_delimiter = ',')]
+    probabilities: Option<Vec<f64>>,
+    /// Файл истории спроса для оценки вероятностей (для riskbuild; способ оценки — --criterion)
+    #[arg(long)]
+    history: Option<String>,
+    /// Цена закупки единицы (для riskbuild)
+    #[arg(long)]
+    buy_price: Option<f64>,
+    /// Цена продажи единицы (для riskbuild)
+    #[arg(long)]
+    sell_price: Option<f64>,
+    /// Цена реализации непроданного остатка (для riskbuild)
+    #[arg(long)]
+    salvage_price: Option<f64>,
+    /// Издержки хранения единицы остатка (для riskbuild)
+    #[arg(long)]
+    holding_cost: Option<f64>,
+    /// Штраф за единицу неудовлетворённого спроса (для riskbuild)
+    #[arg(long)]
+    shortage_cost: Option<f64>,
+    /// Постоянные затраты на ненулевой заказ (для riskbuild)
+    #[arg(long)]
+    fixed_cost: Option<f64>,
+    /// Файл, в который riskbuild сохраняет сгенерированную задачу
+    #[arg(long)]
+    output: Option<String>,
+    /// Диапазон параметра критерия через запятую (для sweep)
+    #[arg(long, value_delimiter = ',', default_value = "0,1", allow_hyphen_values = true)]
+    range: Vec<f64>,
 }
 
 fn main() -> Result<()> {
@@ -46,8 +148,7 @@ fn main() -> Result<()> {
 
         // // 2. Кластеризация
         // {
-        //     // путь к Python можно захардкодить при необходимости
-        //     ClusteringSolver::solve(None)?;
+        //     ClusteringSolver::solve()?;
         //     println!("Кластеризация завершена: смотри data/answer_for_clustering.txt и dendrogram.png");
         // }
 
@@ -92,22 +193,228 @@ fn main() -> Result<()> {
 
     // ---- Старый режим через CLI ----
     match cli.method.as_deref() {
-        Some("deterministic") | Some("multicriteria") | Some("risk") => {
+        Some("deterministic") | Some("multicriteria") | Some("risk") | Some("information") | Some("utility") => {
             let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
-            let spec = core::parser::read_spec(&input_path)?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), cli.method.as_deref() == Some("multicriteria"))?;
+            if cli.method.as_deref() == Some("utility") {
+                apply_dominance(&mut spec, cli.dominance.as_deref())?;
+            }
             let result = match cli.method.as_deref() {
                 Some("deterministic") => DeterministicSolver::solve(&spec),
                 Some("multicriteria") => WeightedSumSolver::solve(&spec),
-                Some("risk") => RiskSolver::solve(&spec),
+                Some("risk") => RiskSolver::solve(&spec, &cli.levels),
+                Some("information") => RiskSolver::information(&spec),
+                Some("utility") => ExpectedUtilitySolver::solve(&spec),
+                _ => unreachable!(),
+            }?;
+            report(result, &spec);
+        }
+        Some("decision") | Some("probabilistic") | Some("bayesian") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let criterion = cli.criterion.ok_or_else(|| anyhow::anyhow!("Criterion required (--criterion)"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            if cli.observe.is_some() {
+                spec.observations = cli.observe;
+            }
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            if cli.method.as_deref() != Some("decision") {
+                apply_dominance(&mut spec, cli.dominance.as_deref())?;
+            }
+            let result = match cli.method.as_deref() {
+                Some("decision") => DecisionRulesSolver::solve(&spec, &criterion, cli.param),
+                Some("probabilistic") => ProbabilisticRulesSolver::solve(&spec, &criterion, cli.param),
+                Some("bayesian") => BayesUpdateSolver::solve(&spec, &criterion, cli.param),
                 _ => unreachable!(),
             }?;
+            report(result, &spec);
+        }
+        Some("compare") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            apply_dominance(&mut spec, cli.dominance.as_deref())?;
+            let result = CriteriaConsensusSolver::solve(&spec)?;
+            report(result, &spec);
+        }
+        Some("imprecise") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            let criterion = cli.criterion.as_deref().unwrap_or("gamma-maximin");
+            let result = ImpreciseProbabilitySolver::solve(&spec, criterion)?;
+            report(result, &spec);
+        }
+        Some("sensitivity") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            let sensitivity = ProbabilitySensitivitySolver::solve(&spec)?;
+            report(sensitivity.result, &spec);
+            if let Some(plot) = sensitivity.plot {
+                println!("Ожидаемые значения от вероятности первого состояния (| — точки безразличия):");
+                print!("{}", plot);
+            }
+        }
+        Some("sweep") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let criterion = cli.criterion.ok_or_else(|| anyhow::anyhow!("Criterion required (--criterion)"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            let [lo, hi] = cli.range[..] else {
+                anyhow::bail!("Диапазон параметра задаётся двумя числами: --range lo,hi");
+            };
+            let sweep = ParameterSweepSolver::solve(&spec, &criterion, (lo, hi))?;
+            report(sweep.result, &spec);
+            println!("График оценок от параметра (| — точки переключения):");
+            print!("{}", sweep.plot);
+        }
+        Some("maxent") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let spec = core::parser::read_spec(&input_path)?;
+            let estimate = max_entropy(&spec)?;
+            print_weights(&format!("Вероятности состояний (максимум энтропии, H = {:.4})", estimate.entropy), &estimate.probabilities);
+            print_table(&estimate.constraints);
+            print_notes(&estimate.notes);
+        }
+        Some("history") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let history = read_history(&input_path)?;
+            let method = EstimationMethod::from_name(cli.criterion.as_deref().unwrap_or("laplace"))?;
+            let spec = cli.spec.as_deref().map(|path| load_spec(path, cli.missing.as_deref(), false)).transpose()?;
+            let states = history_states(&history, spec.as_ref())?;
+            let estimate = estimate_probabilities(&history.observations, &states, method, cli.param)?;
+            let probabilities: Vec<(String, f64)> = states.iter().map(|(s, _)| s.clone()).zip(estimate.probabilities.iter().cloned()).collect();
+            print_weights(&format!("Вероятности состояний по истории ({})", method.name()), &probabilities);
+            for table in &estimate.tables {
+                print_table(table);
+            }
+            print_notes(&estimate.notes);
+            if let Some(mut spec) = spec {
+                if spec.state_probabilities.is_some() || spec.probability_bounds.is_some() {
+                    spec.notes.push("Вероятности состояний из задачи заменены оценкой по истории".to_string());
+                }
+                spec.notes.push(format!("Вероятности состояний оценены по {} наблюдениям ({})", history.observations.len(), method.name()));
+                spec.state_probabilities = Some(estimate.probabilities);
+                spec.probability_bounds = None;
+                let result = RiskSolver::solve(&spec, &cli.levels)?;
+                report(result, &spec);
+            }
+        }
+        Some("anp") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let result = AnpSolver::solve(&input_path)?;
             print_result(&result);
         }
+        Some("bwm") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let bwm = BwmSolver::solve(&input_path)?;
+            print_weights(&format!("Веса критериев (BWM, ξ={:.4}, CR={:.4})", bwm.xi, bwm.cr), &bwm.weights);
+            if let Some(spec_path) = cli.spec {
+                let mut spec = load_spec(&spec_path, cli.missing.as_deref(), true)?;
+                spec.set_weights(&bwm.weights)?;
+                let result = WeightedSumSolver::solve(&spec)?;
+                report(result, &spec);
+            }
+        }
+        Some("weights") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let method = WeightingMethod::from_name(cli.criterion.as_deref().unwrap_or("entropy"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            let mut weights = objective_weights(&spec, method, cli.order.as_deref())?;
+            print_weights(&format!("Объективные веса ({})", method.name()), &weights);
+            if cli.combine {
+                let subjective: Vec<(String, f64)> = spec.criteria.iter().map(|c| (c.id.clone(), c.weight.unwrap_or(1.0))).collect();
+                weights = combine_weights(&subjective, &weights, cli.param);
+                print_weights("Объединённые веса", &weights);
+            }
+            spec.set_weights(&weights)?;
+            let result = WeightedSumSolver::solve(&spec)?;
+            report(result, &spec);
+        }
+        Some("maut") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            let aggregation = Aggregation::from_name(cli.criterion.as_deref().unwrap_or("additive"))?;
+            let result = MautSolver::solve(&spec, aggregation)?;
+            report(result, &spec);
+        }
+        Some("choquet") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            let result = ChoquetSolver::solve(&spec)?;
+            report(result, &spec);
+        }
+        Some("interval") | Some("fuzzy") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            let mode = if cli.method.as_deref() == Some("interval") { UncertaintyMode::Interval } else { UncertaintyMode::Fuzzy };
+            let result = FuzzySolver::solve(&spec, cli.criterion.as_deref().unwrap_or("weighted-sum"), mode)?;
+            report(result, &spec);
+        }
+        Some("dominance") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let mut spec = load_spec(&input_path, cli.missing.as_deref(), false)?;
+            apply_orientation(&mut spec, cli.orientation.as_deref())?;
+            let order = DominanceOrder::from_name(cli.criterion.as_deref().unwrap_or("ssd"))?;
+            let result = StochasticDominanceSolver::solve(&spec, order)?;
+            report(result, &spec);
+        }
+        Some("tree") => {
+            let input_path = cli.input.ok_or_else(|| anyhow::anyhow!("Input path required"))?;
+            let mode = TreeMode::from_name(cli.criterion.as_deref().unwrap_or("ev"))?;
+            let solved = DecisionTreeSolver::solve(&input_path, mode)?;
+            print_result(&solved.result);
+            println!("Дерево решений (* — оптимальная ветвь):");
+            print!("{}", solved.text);
+            if let Some(dot_path) = cli.dot {
+                std::fs::write(&dot_path, &solved.dot)?;
+                println!("Дерево сохранено в {}", dot_path);
+            }
+        }
         Some("riskbuild") => {
-            tools::risk_builder::run()?;
+            // конфигурация из файла (или исходный пример), флаги командной строки её дополняют
+            let mut config = match cli.input.as_deref() {
+                Some(path) => NewsvendorConfig::read(path)?,
+                None => NewsvendorConfig::default(),
+            };
+            if let Some(demands) = cli.demands {
+                config.demands = demands;
+            }
+            if cli.quantities.is_some() {
+                config.quantities = cli.quantities;
+            }
+            if cli.probabilities.is_some() {
+                config.state_probabilities = cli.probabilities;
+                config.history = None;
+            }
+            if cli.history.is_some() {
+                config.history = cli.history;
+                config.state_probabilities = None;
+            }
+            if cli.criterion.is_some() {
+                config.estimation = cli.criterion;
+            }
+            let costs = [
+                (cli.buy_price, &mut config.buy_price),
+                (cli.sell_price, &mut config.sell_price),
+                (cli.salvage_price, &mut config.salvage_price),
+                (cli.holding_cost, &mut config.holding_cost),
+                (cli.shortage_cost, &mut config.shortage_cost),
+                (cli.fixed_cost, &mut config.fixed_cost),
+            ];
+            for (value, field) in costs {
+                if let Some(value) = value {
+                    *field = value;
+                }
+            }
+            if let Some(output) = cli.output {
+                config.output = Some(output);
+            }
+            let (spec, result) = tools::risk_builder::run(&config, &cli.levels)?;
+            report(result, &spec);
         }
         Some("clustering") => {
-            ClusteringSolver::solve(None)?;
+            ClusteringSolver::solve()?;
             println!("Кластеризация завершена: см.: data/answer_for_clustering.txt и dendrogram.png");
         }
         Some(other) => anyhow::bail!("Неизвестный метод: {}", other),
@@ -116,3 +423,47 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+
+/// Чтение задачи с применением политики для пропусков и оценкой вероятностей состояний
+/// по максимуму энтропии (если заданы `probability_constraints`).
+/// `renormalize` — решатель сам перенормирует веса по известным критериям, и пропуски можно оставить.
+fn load_spec(path: &str, missing: Option<&str>, renormalize: bool) -> Result<ProblemSpec> {
+    let mut spec = core::parser::read_spec(path)?;
+    let policy = missing.map(MissingPolicy::from_name).transpose()?;
+    if let Some(policy) = policy {
+        apply_missing_policy(&mut spec, policy)?;
+    }
+    apply_max_entropy(&mut spec)?;
+    if !(renormalize && policy == Some(MissingPolicy::Renormalize)) {
+        ensure_complete(&spec)?;
+    }
+    Ok(spec)
+}
+
+
+/// Предварительный отбор альтернатив по стохастическому доминированию (если задан порядок)
+fn apply_dominance(spec: &mut ProblemSpec, order: Option<&str>) -> Result<()> {
+    if let Some(order) = order {
+        prefilter(spec, DominanceOrder::from_name(order)?)?;
+    }
+    Ok(())
+}
+
+
+/// Ориентация матрицы из командной строки (если задана) вместо указанной в задаче
+fn apply_orientation(spec: &mut ProblemSpec, orientation: Option<&str>) -> Result<()> {
+    if let Some(orientation) = orientation {
+        spec.orientation = Orientation::from_name(orientation)?;
+    }
+    Ok(())
+}
+
+
+/// Печать результата задачи из файла: к нему добавляются пояснения о подготовке данных
+/// и исходные метки качественных шкал
+fn report(mut result: DecisionResult, spec: &ProblemSpec) {
+    result.notes.extend(spec.notes.iter().cloned());
+    result.notes.extend(spec.label_notes());
+    print_result(&result);
+}
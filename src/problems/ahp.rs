@@ -16,6 +16,14 @@ struct AhpAlternative {
     values: Vec<f64>, // (в том же порядке, что criteria)
 }
 
+/// Показатели согласованности матрицы парных сравнений
+#[derive(Debug, Clone, Copy)]
+pub struct Consistency {
+    pub lambda_max: f64,
+    pub ci: f64,
+    pub cr: f64,
+}
+
 pub struct AhpSolver;
 
 impl AhpSolver {
@@ -27,64 +35,32 @@ impl AhpSolver {
         let m = input.alternatives.len();
 
         // 1. Проверка ввода
-        if input.criteria_comparison.len() != n {
-            anyhow::bail!("Матрица критериев должна быть {}×{}", n, n);
-        }
+        check_square(&input.criteria_comparison, n, "Матрица критериев")?;
 
-        // 2. Нормирование строк
-        let mut col_sums = vec![0.0; n];
-        for j in 0..n {
-            for i in 0..n {
-                col_sums[j] += input.criteria_comparison[i][j];
-            }
-        }
-        let mut normalized = vec![vec![0.0; n]; n];
-        for i in 0..n {
-            for j in 0..n {
-                normalized[i][j] = input.criteria_comparison[i][j] / col_sums[j];
-            }
-        }
-        let mut crit_weights = vec![0.0; n];
-        for i in 0..n {
-            let sum_row: f64 = normalized[i].iter().sum();
-            crit_weights[i] = sum_row / n as f64;
-        }
+        // 2. Нормирование столбцов и веса критериев
+        let crit_weights = priority_vector(&input.criteria_comparison);
         println!("Критерии и их веса (AHP):");
         for (i, w) in crit_weights.iter().enumerate() {
             println!("  {} -> {:.4}", input.criteria[i], w);
         }
 
         // 3. Проверка согласованности
-        let mut lambda_vec = vec![0.0; n];
-        for i in 0..n {
-            let mut s = 0.0;
-            for j in 0..n {
-                s += input.criteria_comparison[i][j] * crit_weights[j];
-            }
-            lambda_vec[i] = s / crit_weights[i];
-        }
-        let lambda_max: f64 = lambda_vec.iter().sum::<f64>() / n as f64;
-        let ci = (lambda_max - n as f64) / (n as f64 - 1.0);
-        let ri = choose_index(n);
-        let cr = if ri > 0.0 { ci / ri } else { 0.0 };
+        let Consistency { lambda_max, ci, cr } = consistency(&input.criteria_comparison, &crit_weights);
 
         // 4. Локальные приоритеты альтернатив 
         let mut local = vec![vec![0.0; m]; n];
-        for crit in 0..n {
-            let mut sum_col = 0.0;
-            for alt in 0..m {
-                sum_col += input.alternatives[alt].values[crit];
-            }
-            for alt in 0..m {
-                local[crit][alt] = input.alternatives[alt].values[crit] / sum_col;
+        for (crit, row) in local.iter_mut().enumerate() {
+            let sum_col: f64 = input.alternatives.iter().map(|a| a.values[crit]).sum();
+            for (alt, cell) in row.iter_mut().enumerate() {
+                *cell = input.alternatives[alt].values[crit] / sum_col;
             }
         }
 
         // 5. Синтез: глобальные веса альтернатив
         let mut global_scores = vec![0.0; m];
-        for alt in 0..m {
+        for (alt, score) in global_scores.iter_mut().enumerate() {
             for crit in 0..n {
-                global_scores[alt] += crit_weights[crit] * local[crit][alt];
+                *score += crit_weights[crit] * local[crit][alt];
             }
         }
 
@@ -96,7 +72,7 @@ impl AhpSolver {
             .map(|(i, a)| (a.id.clone(), global_scores[i]))
             .collect();
 
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
 
         let best_val = scores[0].1;
         let chosen: Vec<String> = scores
@@ -112,10 +88,53 @@ impl AhpSolver {
                 "AHP (λ_max={:.3}, CI={:.3}, CR={:.3})",
                 lambda_max, ci, cr
             ),
+            weights: Some(input.criteria.iter().cloned().zip(crit_weights).collect()),
+            ..Default::default()
         })
     }
 }
 
+/// Проверка, что матрица парных сравнений имеет размер n×n; name — какая матрица, для сообщения об ошибке
+pub fn check_square(matrix: &[Vec<f64>], n: usize, name: &str) -> Result<()> {
+    if matrix.len() != n || matrix.iter().any(|row| row.len() != n) {
+        anyhow::bail!("{} должна быть {}×{}", name, n, n);
+    }
+    Ok(())
+}
+
+/// Вектор приоритетов: нормирование столбцов и усреднение по строкам
+pub fn priority_vector(matrix: &[Vec<f64>]) -> Vec<f64> {
+    let n = matrix.len();
+    let mut col_sums = vec![0.0; n];
+    for row in matrix {
+        for (j, v) in row.iter().enumerate() {
+            col_sums[j] += v;
+        }
+    }
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(col_sums.iter()).map(|(v, s)| v / s).sum::<f64>() / n as f64)
+        .collect()
+}
+
+/// λ_max, индекс (CI) и отношение (CR) согласованности для найденных весов
+pub fn consistency(matrix: &[Vec<f64>], weights: &[f64]) -> Consistency {
+    let n = matrix.len();
+    if n < 2 {
+        return Consistency { lambda_max: n as f64, ci: 0.0, cr: 0.0 };
+    }
+    let lambda_max: f64 = matrix
+        .iter()
+        .zip(weights.iter())
+        .map(|(row, w)| row.iter().zip(weights.iter()).map(|(a, wj)| a * wj).sum::<f64>() / w)
+        .sum::<f64>()
+        / n as f64;
+    let ci = (lambda_max - n as f64) / (n as f64 - 1.0);
+    let ri = choose_index(n);
+    let cr = if ri > 0.0 { ci / ri } else { 0.0 };
+    Consistency { lambda_max, ci, cr }
+}
+
 /// Случайный индекс (таблица Саати)
 fn choose_index(n: usize) -> f64 {
     match n {
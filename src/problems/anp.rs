@@ -0,0 +1,272 @@
+// -----------------------------
+// src/problems/anp.rs
+// -----------------------------
+//! Метод аналитических сетей (ANP).
+//!
+//! В отличие от `AhpSolver`, критерии могут зависеть друг от друга и от альтернатив
+//! (обратная связь). Сеть задаётся кластерами узлов и парными сравнениями влияний;
+//! по ним строится невзвешенная суперматрица, затем взвешенная (с учётом сравнения
+//! кластеров) и предельная, из которой берутся приоритеты альтернатив.
+//! Векторы приоритетов и согласованность считаются теми же функциями, что и в AHP.
+use anyhow::Result;
+use crate::core::base::DecisionResult;
+use crate::problems::ahp::{check_square, consistency, priority_vector};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+struct AnpInput {
+    clusters: Vec<AnpCluster>,
+    alternatives_cluster: String,      // кластер, содержащий альтернативы
+    goal: Option<String>,              // узел-цель: приоритеты берутся из его столбца
+    node_comparisons: Vec<NodeComparison>,
+    #[serde(default)]
+    cluster_comparisons: Vec<ClusterComparison>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnpCluster {
+    id: String,
+    nodes: Vec<String>,
+}
+
+/// Сравнение узлов кластера `cluster` по влиянию на узел `parent`
+#[derive(Debug, Deserialize)]
+struct NodeComparison {
+    parent: String,
+    cluster: String,
+    nodes: Option<Vec<String>>, // подмножество узлов кластера (по умолчанию все)
+    matrix: Vec<Vec<f64>>,
+}
+
+/// Сравнение кластеров `clusters` по влиянию на кластер `parent`
+#[derive(Debug, Deserialize)]
+struct ClusterComparison {
+    parent: String,
+    clusters: Vec<String>,
+    matrix: Vec<Vec<f64>>,
+}
+
+pub struct AnpSolver;
+
+impl AnpSolver {
+    pub fn solve(input_path: &str) -> Result<DecisionResult> {
+        let s = fs::read_to_string(input_path)?;
+        let input: AnpInput = serde_json::from_str(&s)?;
+
+        // 1. Нумерация узлов: (кластер, имя) -> индекс в суперматрице
+        let mut nodes: Vec<(usize, String)> = Vec::new();
+        for (c, cluster) in input.clusters.iter().enumerate() {
+            for node in &cluster.nodes {
+                if nodes.iter().any(|(_, n)| n == node) {
+                    anyhow::bail!("Узел {} встречается в сети несколько раз", node);
+                }
+                nodes.push((c, node.clone()));
+            }
+        }
+        let n = nodes.len();
+        let node_index = |name: &str| nodes.iter().position(|(_, n)| n == name)
+            .ok_or_else(|| anyhow::anyhow!("Неизвестный узел: {}", name));
+        let cluster_index = |name: &str| input.clusters.iter().position(|c| c.id == name)
+            .ok_or_else(|| anyhow::anyhow!("Неизвестный кластер: {}", name));
+        let alt_cluster = cluster_index(&input.alternatives_cluster)?;
+
+        // 2. Невзвешенная суперматрица: столбец = узел-родитель, блок = вектор приоритетов кластера
+        let mut unweighted = vec![vec![0.0; n]; n];
+        let mut max_cr: f64 = 0.0;
+        for cmp in &input.node_comparisons {
+            let parent = node_index(&cmp.parent)?;
+            let cluster = &input.clusters[cluster_index(&cmp.cluster)?];
+            let members = cmp.nodes.as_ref().unwrap_or(&cluster.nodes);
+            if let Some(stranger) = members.iter().find(|m| !cluster.nodes.contains(m)) {
+                anyhow::bail!("Узел {} не принадлежит кластеру {}", stranger, cluster.id);
+            }
+            check_square(
+                &cmp.matrix,
+                members.len(),
+                &format!("Матрица сравнения узлов кластера {} относительно {}", cluster.id, cmp.parent),
+            )?;
+
+            let weights = priority_vector(&cmp.matrix);
+            let cr = consistency(&cmp.matrix, &weights).cr;
+            if cr > 0.1 {
+                println!("Внимание: CR={:.3} для сравнения {} относительно {}", cr, cluster.id, cmp.parent);
+            }
+            max_cr = max_cr.max(cr);
+            for (member, w) in members.iter().zip(weights) {
+                unweighted[node_index(member)?][parent] = w;
+            }
+        }
+
+        // 3. Веса кластеров: по сравнениям или поровну среди влияющих кластеров
+        let k = input.clusters.len();
+        let influences = |c: usize, parent: usize| {
+            (0..n).any(|i| nodes[i].0 == c && (0..n).any(|j| nodes[j].0 == parent && unweighted[i][j] > 0.0))
+        };
+        let n_influencing: Vec<usize> = (0..k).map(|parent| (0..k).filter(|&c| influences(c, parent)).count()).collect();
+        let mut cluster_weights: Vec<Vec<f64>> = (0..k) // [влияющий][родитель]
+            .map(|c| {
+                (0..k)
+                    .map(|parent| if influences(c, parent) { 1.0 / n_influencing[parent] as f64 } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        for cmp in &input.cluster_comparisons {
+            let parent = cluster_index(&cmp.parent)?;
+            check_square(&cmp.matrix, cmp.clusters.len(), &format!("Матрица сравнения кластеров относительно {}", cmp.parent))?;
+            let weights = priority_vector(&cmp.matrix);
+            max_cr = max_cr.max(consistency(&cmp.matrix, &weights).cr);
+            for row in cluster_weights.iter_mut() {
+                row[parent] = 0.0;
+            }
+            for (name, w) in cmp.clusters.iter().zip(weights) {
+                cluster_weights[cluster_index(name)?][parent] = w;
+            }
+        }
+
+        // 4. Взвешенная суперматрица: блоки умножаются на веса кластеров, столбцы нормируются.
+        //    Узлы без влияний (обычно альтернативы) замыкаются сами на себя.
+        let mut weighted = vec![vec![0.0; n]; n];
+        for j in 0..n {
+            for i in 0..n {
+                weighted[i][j] = unweighted[i][j] * cluster_weights[nodes[i].0][nodes[j].0];
+            }
+            let col_sum: f64 = (0..n).map(|i| weighted[i][j]).sum();
+            if col_sum > 0.0 {
+                for row in weighted.iter_mut() {
+                    row[j] /= col_sum;
+                }
+            } else {
+                weighted[j][j] = 1.0;
+            }
+        }
+
+        // 5. Предельная суперматрица
+        let (limit, iterations) = limit_supermatrix(&weighted);
+
+        // 6. Приоритеты: столбец цели или среднее по столбцам узлов, не являющихся альтернативами
+        let source: Vec<usize> = match &input.goal {
+            Some(goal) => vec![node_index(goal)?],
+            None => (0..n).filter(|&j| nodes[j].0 != alt_cluster).collect(),
+        };
+        if source.is_empty() {
+            anyhow::bail!("В сети нет узлов, кроме альтернатив");
+        }
+        let priority = |i: usize| source.iter().map(|&j| limit[i][j]).sum::<f64>() / source.len() as f64;
+
+        println!("Предельные приоритеты узлов (ANP):");
+        for (c, cluster) in input.clusters.iter().enumerate() {
+            if c == alt_cluster {
+                continue;
+            }
+            // в иерархии без обратной связи вес целиком уходит в альтернативы
+            let total: f64 = (0..n).filter(|&i| nodes[i].0 == c).map(priority).sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for i in (0..n).filter(|&i| nodes[i].0 == c) {
+                println!("  [{}] {} -> {:.4}", cluster.id, nodes[i].1, priority(i) / total);
+            }
+        }
+
+        let alts: Vec<usize> = (0..n).filter(|&i| nodes[i].0 == alt_cluster).collect();
+        let total: f64 = alts.iter().map(|&i| priority(i)).sum();
+        if total <= 0.0 {
+            anyhow::bail!("Альтернативы не получают влияния ни от одного узла сети");
+        }
+        let mut scores: Vec<(String, f64)> = alts
+            .iter()
+            .map(|&i| (nodes[i].1.clone(), priority(i) / total))
+            .collect();
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let best_val = scores[0].1;
+        let chosen: Vec<String> = scores
+            .iter()
+            .filter(|(_, v)| (*v - best_val).abs() < 1e-9)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        Ok(DecisionResult {
+            chosen,
+            scores,
+            method: format!("ANP (узлов={}, итераций={}, max CR={:.3})", n, iterations, max_cr),
+            ..Default::default()
+        })
+    }
+}
+
+fn mat_mul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = a.len();
+    let mut c = vec![vec![0.0; n]; n];
+    for (i, row) in c.iter_mut().enumerate() {
+        for (k, a_ik) in a[i].iter().enumerate() {
+            if *a_ik == 0.0 {
+                continue;
+            }
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell += a_ik * b[k][j];
+            }
+        }
+    }
+    c
+}
+
+/// Возведение стохастической по столбцам матрицы в степень до сходимости.
+/// Если степени циклически колеблются, берётся среднее по Чезаро за 2520 шагов
+/// (НОК чисел 1..10, т.е. точное среднее для циклов длины до 10).
+fn limit_supermatrix(w: &[Vec<f64>]) -> (Vec<Vec<f64>>, usize) {
+    const MAX_ITER: usize = 2000;
+    const CESARO_WINDOW: usize = 2520;
+
+    let mut power = w.to_vec();
+    for iter in 1..=MAX_ITER {
+        let next = mat_mul(&power, w);
+        let diff = next
+            .iter()
+            .zip(power.iter())
+            .flat_map(|(r1, r2)| r1.iter().zip(r2.iter()).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        power = next;
+        if diff < 1e-10 {
+            return (power, iter);
+        }
+    }
+
+    let n = w.len();
+    let mut avg = vec![vec![0.0; n]; n];
+    for _ in 0..CESARO_WINDOW {
+        for (avg_row, p_row) in avg.iter_mut().zip(power.iter()) {
+            for (a, p) in avg_row.iter_mut().zip(p_row.iter()) {
+                *a += p / CESARO_WINDOW as f64;
+            }
+        }
+        power = mat_mul(&power, w);
+    }
+    (avg, MAX_ITER + CESARO_WINDOW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_of_regular_supermatrix() {
+        // стационарное распределение: 0.5·π1 + 0.2·π2 = π1 ⇒ π = (2/7, 5/7) в каждом столбце
+        let w = vec![vec![0.5, 0.2], vec![0.5, 0.8]];
+        let (limit, _) = limit_supermatrix(&w);
+        for (row, expected) in limit.iter().zip([2.0 / 7.0, 5.0 / 7.0]) {
+            assert!(row.iter().all(|v| (v - expected).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn limit_of_cyclic_supermatrix() {
+        // степени чередуются между перестановкой и единичной матрицей — среднее по Чезаро равно 1/2
+        let w = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+        let (limit, iterations) = limit_supermatrix(&w);
+        assert!(iterations > 2000);
+        assert!(limit.iter().flatten().all(|v| (v - 0.5).abs() < 1e-12));
+    }
+}
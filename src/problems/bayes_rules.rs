@@ -1,14 +1,19 @@
-use crate::core::base::{ProblemSpec, DecisionResult};
+use crate::core::base::{ProblemSpec, DecisionResult, Orientation, ResultTable};
+use crate::problems::decision_rules::regret_matrix;
+use crate::problems::missing::ensure_complete;
 use anyhow::Result;
 
 pub struct ProbabilisticRulesSolver;
 
 impl ProbabilisticRulesSolver {
-    /// criterion: "bayes" | "ferstner" | "hodge-lehman"
+    /// criterion: "bayes" | "ferstner" | "hodge-lehman" | "hermeyer" | "bayes-regret" | "minimax-regret-p"
     /// lambda используется для ferstner и hodge-lehman
+    /// bayes-regret — Байес–Лаплас на матрице сожалений (ожидаемые упущенные возможности, EOL) → min;
+    /// minimax-regret-p — минимакс сожалений, взвешенных вероятностями: max_j p_j·r_ij → min
+    /// Для spec.orientation = loss формулы зеркальны (см. `criterion_score`), оценки минимизируются.
     pub fn solve(spec: &ProblemSpec, criterion: &str, lambda: Option<f64>) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
         let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for probabilistic criteria"))?;
-        let n_alt = spec.alternatives.len();
         let n_states = probs.len();
 
         // проверка размеров
@@ -19,48 +24,143 @@ impl ProbabilisticRulesSolver {
         }
 
         let mut scores: Vec<(String,f64)> = Vec::new();
+        let mut tables = Vec::new();
+        let mut notes = Vec::new();
+        let orientation = spec.orientation;
+        // оценки в единицах потерь (потери матрицы или сожаления) минимизируем
+        let mut minimize = orientation == Orientation::Loss;
+        if minimize {
+            notes.push("Значения матрицы — потери: лучшее значение — наименьшее".to_string());
+        }
 
         match criterion {
             "hermeyer" => {
-                for alt in &spec.alternatives {
-                    // score = sum(p_j * value_j)
-                    let score: f64 = alt.values.iter().zip(probs.iter()).map(|(v,p)| v*p).sum();
-                    scores.push((alt.id.clone(), score));
+                // Критерий Гермейера применяется к потерям. Выплаты переводятся в отрицательные потери:
+                // если есть неотрицательные выплаты, из всех элементов вычитается число, на единицу большее
+                // максимального, и max_i min_j(p_j·a'_ij). Потери должны быть положительными (иначе
+                // к ним прибавляется 1 − min), и критерий принимает зеркальный вид min_i max_j(p_j·a'_ij).
+                let values = spec.alternatives.iter().flat_map(|a| a.values.iter().cloned());
+                let (shift, extreme) = match orientation {
+                    Orientation::Gain => {
+                        let max = values.fold(f64::NEG_INFINITY, f64::max);
+                        (if max >= 0.0 { -(max + 1.0) } else { 0.0 }, max)
+                    },
+                    Orientation::Loss => {
+                        let min = values.fold(f64::INFINITY, f64::min);
+                        (if min <= 0.0 { 1.0 - min } else { 0.0 }, min)
+                    },
+                };
+                let losses: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.iter().map(|v| v + shift).collect()).collect();
+                let weighted: Vec<Vec<f64>> = losses.iter().map(|row| row.iter().zip(probs.iter()).map(|(v,p)| v*p).collect()).collect();
+                for (alt, row) in spec.alternatives.iter().zip(weighted.iter()) {
+                    // score = min_j(p_j * a'_ij) для выплат, max_j(p_j * a'_ij) для потерь
+                    scores.push((alt.id.clone(), orientation.worst(row)));
                 }
-            },
-            "bayes" => {
-                // среднее взвешенное по вероятностям
-                for alt in &spec.alternatives {
-                    let score: f64 = alt.values.iter().zip(probs.iter()).map(|(v,p)| v*p).sum();
-                    scores.push((alt.id.clone(), score));
+                let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+                let states = spec.state_names(n_states);
+                if shift < 0.0 {
+                    notes.push(format!("Переход к потерям: a'_ij = a_ij − {} (максимальная выплата {} плюс 1)", -shift, extreme));
+                } else if shift > 0.0 {
+                    notes.push(format!("Потери сделаны положительными: a'_ij = a_ij + {} (минимальная потеря {})", shift, extreme));
                 }
+                tables.push(ResultTable { title: "Матрица потерь a'_ij".to_string(), columns: states.clone(), rows: ids.iter().cloned().zip(losses).collect() });
+                tables.push(ResultTable { title: "Взвешенные потери p_j·a'_ij".to_string(), columns: states, rows: ids.into_iter().zip(weighted).collect() });
             },
-            "ferstner" => {
-                let lam = lambda.unwrap_or(-0.5);
-                for alt in &spec.alternatives {
-                    let mean: f64 = alt.values.iter().zip(probs.iter()).map(|(v,p)| v*p).sum();
-                    let min_val = *alt.values.iter().min_by(|a,b| a.partial_cmp(b).unwrap()).unwrap();
-                    let score = lam*min_val + (1.0-lam)*mean;
+            "bayes-regret" | "minimax-regret-p" => {
+                let matrix: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.clone()).collect();
+                let regrets = regret_matrix(&matrix, orientation);
+                for (alt, row) in spec.alternatives.iter().zip(regrets.iter()) {
+                    let weighted = row.iter().zip(probs.iter()).map(|(r,p)| r*p);
+                    let score = if criterion == "bayes-regret" {
+                        weighted.sum()
+                    } else {
+                        weighted.fold(f64::NEG_INFINITY, f64::max)
+                    };
                     scores.push((alt.id.clone(), score));
                 }
+                let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+                tables.push(ResultTable { title: "Матрица сожалений r_ij".to_string(), columns: spec.state_names(n_states), rows: ids.into_iter().zip(regrets).collect() });
+                minimize = true;
             },
-            "hodge-lehman" => {
-                let lam = lambda.unwrap_or(0.7);
+            _ => {
                 for alt in &spec.alternatives {
-                    let mean: f64 = alt.values.iter().zip(probs.iter()).map(|(v,p)| v*p).sum();
-                    let max_val = *alt.values.iter().max_by(|a,b| a.partial_cmp(b).unwrap()).unwrap();
-                    let score = lam*max_val + (1.0-lam)*mean;
-                    scores.push((alt.id.clone(), score));
+                    scores.push((alt.id.clone(), criterion_score(&alt.values, probs, criterion, lambda, orientation)?));
                 }
             },
-            _ => anyhow::bail!("Unknown criterion: {}", criterion)
         }
 
-        // выбираем максимум
-        scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+        // выбираем максимум (для потерь — минимум)
+        if minimize {
+            scores.sort_by(|a,b| a.1.total_cmp(&b.1));
+        } else {
+            scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        }
         let best_val = scores[0].1;
         let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best_val).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
 
-        Ok(DecisionResult { chosen, scores, method: criterion.to_string() })
+        Ok(DecisionResult { chosen, scores, method: criterion.to_string(), tables, notes, ..Default::default() })
+    }
+}
+
+
+/// Оценка одного распределения выплат или потерь (values с вероятностями probs) по критерию
+/// "bayes" | "ferstner" | "hodge-lehman"; используется и в узлах случая дерева решений.
+/// Ферстнер смешивает среднее с худшим значением, Ходж-Леман — с лучшим
+/// (для потерь худшее — максимум, лучшее — минимум).
+pub fn criterion_score(values: &[f64], probs: &[f64], criterion: &str, lambda: Option<f64>, orientation: Orientation) -> Result<f64> {
+    let mean: f64 = values.iter().zip(probs.iter()).map(|(v,p)| v*p).sum();
+    Ok(match criterion {
+        // среднее взвешенное по вероятностям
+        "bayes" => mean,
+        "ferstner" => {
+            let lam = lambda.unwrap_or(-0.5);
+            lam*orientation.worst(values) + (1.0-lam)*mean
+        },
+        "hodge-lehman" => {
+            let lam = lambda.unwrap_or(0.7);
+            lam*orientation.best(values) + (1.0-lam)*mean
+        },
+        _ => anyhow::bail!("Unknown criterion: {}", criterion)
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::read_spec;
+
+    #[test]
+    fn hermeyer_matrix_example() {
+        let spec = read_spec("data/hermeyer_matrix.json").unwrap();
+        let result = ProbabilisticRulesSolver::solve(&spec, "hermeyer", None).unwrap();
+        // максимальная выплата 3, сдвиг на 4; у A3 наименьшая взвешенная потеря −10·0.14 = −1.4
+        assert_eq!(result.chosen, vec!["A3".to_string()]);
+        assert!((result.scores[0].1 + 1.4).abs() < 1e-9);
+        let score = |id: &str| result.scores.iter().find(|(a, _)| a == id).unwrap().1;
+        assert!((score("A1") + 2.2).abs() < 1e-9);
+        assert!((score("A2") + 1.47).abs() < 1e-9);
+        assert!((score("A4") + 1.68).abs() < 1e-9);
+        assert!((score("A5") + 1.47).abs() < 1e-9);
+
+        // критерий Байеса на тех же данных выбирает другую альтернативу
+        let bayes = ProbabilisticRulesSolver::solve(&spec, "bayes", None).unwrap();
+        assert_eq!(bayes.chosen, vec!["A4".to_string()]);
+    }
+
+    #[test]
+    fn hermeyer_keeps_pure_losses() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "state_probabilities": [0.5, 0.5],
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [
+                {"id": "A1", "values": [-2, -8]},
+                {"id": "A2", "values": [-6, -6]}
+            ]
+        }"#).unwrap();
+        let result = ProbabilisticRulesSolver::solve(&spec, "hermeyer", None).unwrap();
+        assert!(result.notes.is_empty());
+        assert_eq!(result.chosen, vec!["A2".to_string()]);
+        assert!((result.scores[0].1 + 3.0).abs() < 1e-9);
     }
 }
@@ -0,0 +1,95 @@
+// -----------------------------
+// src/problems/bayes_update.rs
+// -----------------------------
+//! Байесовский пересчёт вероятностей состояний по результатам наблюдений.
+//!
+//! Априорные вероятности берутся из `state_probabilities`, правдоподобие исходов эксперимента —
+//! из `likelihood` (P(исход | состояние)), последовательность наблюдённых исходов — из `observations`.
+//! После каждого наблюдения вероятности пересчитываются по формуле Байеса:
+//! P(j | k) = P(k | j)·p_j / Σ_i P(k | i)·p_i, и апостериорные вероятности становятся априорными
+//! для следующего наблюдения. На каждом шаге заново применяется выбранный критерий
+//! `ProbabilisticRulesSolver`, так что видно, как меняется решение.
+use crate::core::base::*;
+use crate::problems::bayes_rules::ProbabilisticRulesSolver;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+
+pub struct BayesUpdateSolver;
+
+
+impl BayesUpdateSolver {
+    /// criterion и lambda — как в `ProbabilisticRulesSolver::solve`
+    pub fn solve(spec: &ProblemSpec, criterion: &str, lambda: Option<f64>) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let prior = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Prior probabilities required (state_probabilities)"))?;
+        let likelihood = spec.likelihood.as_ref().ok_or_else(|| anyhow::anyhow!("Likelihood matrix required for Bayesian updating"))?;
+        let observations = spec.observations.as_ref().ok_or_else(|| anyhow::anyhow!("Observations required for Bayesian updating"))?;
+        likelihood.validate(prior.len())?;
+
+        // шаг 0 — априорные вероятности, далее — после каждого наблюдения
+        let mut steps = vec![("априори".to_string(), prior.clone())];
+        for (t, name) in observations.iter().enumerate() {
+            let k = likelihood.signal_index(name)?;
+            let next = posterior(&steps[t].1, &likelihood.matrix[k])
+                .ok_or_else(|| anyhow::anyhow!("Исход «{}» невозможен при текущих вероятностях состояний", name))?;
+            steps.push((format!("{}: {}", t + 1, name), next));
+        }
+
+        let mut staged = spec.clone();
+        let mut results = Vec::new();
+        for (_, probs) in &steps {
+            staged.state_probabilities = Some(probs.clone());
+            results.push(ProbabilisticRulesSolver::solve(&staged, criterion, lambda)?);
+        }
+
+        let step_names: Vec<String> = steps.iter().map(|(name, _)| name.clone()).collect();
+        let tables = vec![
+            ResultTable {
+                title: "Вероятности состояний".to_string(),
+                columns: spec.state_names(prior.len()),
+                rows: steps.clone(),
+            },
+            ResultTable {
+                title: format!("Оценки по критерию {}", criterion),
+                columns: step_names.clone(),
+                rows: spec.alternatives.iter().map(|alt| {
+                    let values = results.iter()
+                        .map(|r| r.scores.iter().find(|(id, _)| id == &alt.id).map_or(f64::NAN, |(_, v)| *v))
+                        .collect();
+                    (alt.id.clone(), values)
+                }).collect(),
+            },
+        ];
+        let mut notes: Vec<String> = step_names.iter().zip(results.iter())
+            .map(|(name, r)| format!("{}: выбор {}", name, r.chosen.join(", ")))
+            .collect();
+        let (first, last) = (&results[0], &results[results.len() - 1]);
+        notes.push(if first.chosen == last.chosen {
+            "Наблюдения не изменили решение".to_string()
+        } else {
+            format!("Решение изменилось: {} → {}", first.chosen.join(", "), last.chosen.join(", "))
+        });
+
+        let posterior_result = results.pop().unwrap();
+        Ok(DecisionResult {
+            chosen: posterior_result.chosen,
+            scores: posterior_result.scores,
+            method: format!("bayes_update ({})", posterior_result.method),
+            tables,
+            notes,
+            ..Default::default()
+        })
+    }
+}
+
+
+/// Апостериорные вероятности после исхода с правдоподобием `likelihood` по состояниям;
+/// None, если исход имеет нулевую вероятность
+pub fn posterior(prior: &[f64], likelihood: &[f64]) -> Option<Vec<f64>> {
+    let evidence: f64 = prior.iter().zip(likelihood.iter()).map(|(p, l)| p * l).sum();
+    if evidence <= 0.0 {
+        return None;
+    }
+    Some(prior.iter().zip(likelihood.iter()).map(|(p, l)| p * l / evidence).collect())
+}
@@ -0,0 +1,180 @@
+// -----------------------------
+// src/problems/bwm.rs
+// -----------------------------
+//! Метод наилучшего-наихудшего (Best-Worst Method, BWM).
+//!
+//! Вместо полной матрицы n×n, как в AHP, эксперт называет лучший и худший критерии
+//! и задаёт два вектора: предпочтение лучшего над остальными (best-to-others)
+//! и остальных над худшим (others-to-worst). Веса находятся из линейной модели
+//! BWM, решаемой через `good_lp`:
+//!
+//! min ξ  при  |w_B − a_Bj·w_j| ≤ ξ,  |w_j − a_jW·w_W| ≤ ξ,  Σ w_j = 1,  w_j ≥ 0.
+//!
+//! Оптимальное ξ служит показателем согласованности (чем ближе к 0, тем лучше); коэффициент
+//! согласованности CR = ξ / CI(a_BW), где CI — наибольшее ξ при данной оценке a_BW лучшего над худшим
+//! (таблица Резаи для a_BW = 1…9, для дробных оценок — корень уравнения ξ² − (1 + 2a)ξ + a² − a = 0).
+use anyhow::Result;
+use good_lp::{constraint, default_solver, variable, variables, Expression, Solution, SolverModel};
+use serde::Deserialize;
+use std::fs;
+
+/// Индексы согласованности BWM для a_BW = 1, 2, …, 9
+const CONSISTENCY_INDEX: [f64; 9] = [0.00, 0.44, 1.00, 1.63, 2.30, 3.00, 3.73, 4.47, 5.23];
+
+#[derive(Debug, Deserialize)]
+struct BwmInput {
+    criteria: Vec<String>,
+    best: String,
+    worst: String,
+    best_to_others: Vec<f64>,  // a_Bj: насколько лучший критерий предпочтительнее j-го
+    others_to_worst: Vec<f64>, // a_jW: насколько j-й критерий предпочтительнее худшего
+}
+
+/// Веса критериев по BWM вместе с показателем согласованности ξ и коэффициентом CR
+#[derive(Debug)]
+pub struct BwmWeights {
+    pub weights: Vec<(String, f64)>,
+    pub xi: f64,
+    pub cr: f64,
+}
+
+pub struct BwmSolver;
+
+impl BwmSolver {
+    pub fn solve(input_path: &str) -> Result<BwmWeights> {
+        let s = fs::read_to_string(input_path)?;
+        let input: BwmInput = serde_json::from_str(&s)?;
+
+        let n = input.criteria.len();
+        if n < 2 {
+            anyhow::bail!("Для BWM нужно не менее двух критериев");
+        }
+        if input.best_to_others.len() != n || input.others_to_worst.len() != n {
+            anyhow::bail!("Векторы best_to_others и others_to_worst должны иметь длину {}", n);
+        }
+        if input.best_to_others.iter().chain(input.others_to_worst.iter()).any(|&a| a < 1.0) {
+            anyhow::bail!("Оценки предпочтения BWM должны быть не меньше 1");
+        }
+        let position = |name: &str| input.criteria.iter().position(|c| c == name)
+            .ok_or_else(|| anyhow::anyhow!("Неизвестный критерий: {}", name));
+        let b = position(&input.best)?;
+        let w = position(&input.worst)?;
+        if b == w {
+            anyhow::bail!("Лучший и худший критерии должны различаться");
+        }
+        if input.best_to_others[b] != 1.0 || input.others_to_worst[w] != 1.0 {
+            anyhow::bail!(
+                "Сравнение критерия с самим собой должно равняться 1: a_BB = {}, a_WW = {}",
+                input.best_to_others[b], input.others_to_worst[w]
+            );
+        }
+        if (input.best_to_others[w] - input.others_to_worst[b]).abs() > 1e-9 {
+            anyhow::bail!(
+                "Оценка лучшего над худшим не совпадает в векторах: {} и {}",
+                input.best_to_others[w], input.others_to_worst[b]
+            );
+        }
+
+        // Линейная модель BWM
+        let mut vars = variables!();
+        let weights: Vec<_> = (0..n).map(|_| vars.add(variable().min(0.0))).collect();
+        let xi = vars.add(variable().min(0.0));
+
+        let mut model = vars.minimise(xi).using(default_solver);
+        let total: Expression = weights.iter().sum();
+        model = model.with(constraint!(total == 1.0));
+        for j in 0..n {
+            let a_bj = input.best_to_others[j];
+            let a_jw = input.others_to_worst[j];
+            model = model
+                .with(constraint!(weights[b] - a_bj * weights[j] <= xi))
+                .with(constraint!(a_bj * weights[j] - weights[b] <= xi))
+                .with(constraint!(weights[j] - a_jw * weights[w] <= xi))
+                .with(constraint!(a_jw * weights[w] - weights[j] <= xi));
+        }
+        let solution = model.solve()?;
+        let xi_value = solution.value(xi);
+        let ci = consistency_index(input.best_to_others[w]);
+
+        Ok(BwmWeights {
+            weights: input
+                .criteria
+                .iter()
+                .zip(weights.iter())
+                .map(|(c, v)| (c.clone(), solution.value(*v)))
+                .collect(),
+            xi: xi_value,
+            // при a_BW = 1 все критерии равноценны и ξ = 0
+            cr: if ci > 0.0 { xi_value / ci } else { 0.0 },
+        })
+    }
+}
+
+
+/// CI(a_BW): табличное значение для целых оценок 1…9, иначе — корень ξ² − (1 + 2a)ξ + a² − a = 0
+fn consistency_index(a_bw: f64) -> f64 {
+    if a_bw.fract() == 0.0 && (1.0..=9.0).contains(&a_bw) {
+        return CONSISTENCY_INDEX[a_bw as usize - 1];
+    }
+    ((1.0 + 2.0 * a_bw) - (1.0 + 8.0 * a_bw).sqrt()) / 2.0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(name: &str, json: &str) -> Result<BwmWeights> {
+        let path = std::env::temp_dir().join(format!("bwm_{}_{}.json", name, std::process::id()));
+        fs::write(&path, json).unwrap();
+        let result = BwmSolver::solve(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn consistent_comparisons_give_exact_weights() {
+        // a_Bj·a_jW = a_BW для всех j — веса (4, 2, 1)/7 при ξ = 0
+        let result = solve("consistent", r#"{
+            "criteria": ["a", "b", "c"], "best": "a", "worst": "c",
+            "best_to_others": [1, 2, 4], "others_to_worst": [4, 2, 1]
+        }"#).unwrap();
+        for ((_, w), expected) in result.weights.iter().zip([4.0 / 7.0, 2.0 / 7.0, 1.0 / 7.0]) {
+            assert!((w - expected).abs() < 1e-9);
+        }
+        assert!(result.xi.abs() < 1e-9);
+    }
+
+    #[test]
+    fn inconsistent_comparisons_give_consistency_ratio() {
+        // a_Bb·a_bW = 4 ≠ a_BW = 3; w = (13, 7, 4)/24 уравнивает все три отклонения: ξ = 1/24, CI(3) = 1
+        let result = solve("inconsistent", r#"{
+            "criteria": ["a", "b", "c"], "best": "a", "worst": "c",
+            "best_to_others": [1, 2, 3], "others_to_worst": [3, 2, 1]
+        }"#).unwrap();
+        for ((_, w), expected) in result.weights.iter().zip([13.0 / 24.0, 7.0 / 24.0, 4.0 / 24.0]) {
+            assert!((w - expected).abs() < 1e-9);
+        }
+        assert!((result.xi - 1.0 / 24.0).abs() < 1e-9);
+        assert!((result.cr - 1.0 / 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn consistency_index_matches_table() {
+        // табличные значения — корни ξ² − (1 + 2a)ξ + a² − a = 0, округлённые до сотых
+        for a in 2..=9 {
+            let root = consistency_index(a as f64 + 1e-12);
+            assert!((root - consistency_index(a as f64)).abs() < 0.005);
+        }
+        assert!((consistency_index(2.5) - (6.0 - 21f64.sqrt()) / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn self_comparison_must_be_one() {
+        let result = solve("self", r#"{
+            "criteria": ["a", "b", "c"], "best": "a", "worst": "c",
+            "best_to_others": [2, 2, 4], "others_to_worst": [4, 2, 1]
+        }"#);
+        assert!(result.is_err());
+    }
+}
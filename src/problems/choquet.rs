@@ -0,0 +1,295 @@
+// -----------------------------
+// src/problems/choquet.rs
+// -----------------------------
+//! Агрегация при взаимодействующих критериях: интеграл Шоке и операторы OWA.
+//!
+//! Взвешенная сумма предполагает независимость критериев. Интеграл Шоке по нечёткой мере μ
+//! (ёмкости) позволяет задать взаимодействие: μ({цена, качество}) больше суммы мер
+//! по отдельности — критерии дополняют друг друга, меньше — дублируют.
+//! Ёмкость задаётся в `ProblemSpec.capacity` полностью или 2-аддитивной моделью.
+//! В результат добавляются индексы важности Шепли и индексы взаимодействия.
+//!
+//! OWA — частный случай интеграла Шоке с симметричной мерой: веса относятся не к критериям,
+//! а к позициям упорядоченных значений. Используется в `DecisionRulesSolver` ("owa", "wowa").
+use crate::core::base::*;
+use crate::problems::weighting::normalized_columns;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+
+pub struct ChoquetSolver;
+
+
+impl ChoquetSolver {
+    pub fn solve(spec: &ProblemSpec) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let n = spec.criteria.len();
+        if n == 0 || spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+        if spec.alternatives.iter().any(|a| a.values.len() != n) { anyhow::bail!("Alternative values length mismatch") }
+        if n > 20 { anyhow::bail!("Слишком много критериев для ёмкости: {}", n) }
+
+        let mu = capacity(spec)?;
+        let shapley = shapley_values(&mu, n);
+        let interaction = interaction_indices(&mu, n);
+
+        // значения приводятся к общей шкале [0, 1] с учётом направления критериев
+        let cols = normalized_columns(spec);
+        let rows: Vec<(String, Vec<f64>)> = spec.alternatives.iter().enumerate()
+            .map(|(i, alt)| (alt.id.clone(), cols.iter().map(|c| c[i]).collect()))
+            .collect();
+
+        let mut scores: Vec<(String, f64)> = rows.iter().map(|(id, x)| (id.clone(), choquet(x, &mu))).collect();
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+
+        let ids: Vec<String> = spec.criteria.iter().map(|c| c.id.clone()).collect();
+        let tables = vec![
+            ResultTable { title: "Нормированные значения".to_string(), columns: ids.clone(), rows },
+            ResultTable {
+                title: "Индексы важности Шепли".to_string(),
+                columns: ids.clone(),
+                rows: vec![("φ".to_string(), shapley)],
+            },
+            ResultTable {
+                title: "Индексы взаимодействия".to_string(),
+                columns: ids.clone(),
+                rows: ids.into_iter().zip(interaction).collect(),
+            },
+        ];
+
+        Ok(DecisionResult { chosen, scores, method: "choquet_integral".to_string(), tables, ..Default::default() })
+    }
+}
+
+
+/// Ёмкость как вектор μ по битовым маскам подмножеств критериев (длина 2^n)
+fn capacity(spec: &ProblemSpec) -> Result<Vec<f64>> {
+    let n = spec.criteria.len();
+    let full = (1usize << n) - 1;
+    let index = |id: &str| spec.criteria.iter().position(|c| c.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Неизвестный критерий в ёмкости: {}", id));
+    let mask = |ids: &[String]| -> Result<usize> {
+        ids.iter().try_fold(0usize, |m, id| Ok(m | (1 << index(id)?)))
+    };
+
+    let mut mu = vec![0.0; 1 << n];
+    match spec.capacity.as_ref().ok_or_else(|| anyhow::anyhow!("Capacity required for Choquet integral"))? {
+        Capacity::Full { subsets } => {
+            let mut given = vec![false; 1 << n];
+            given[0] = true;
+            for s in subsets {
+                let m = mask(&s.criteria)?;
+                mu[m] = s.value;
+                given[m] = true;
+            }
+            if !given[full] {
+                mu[full] = 1.0;
+                given[full] = true;
+            }
+            if let Some(missing) = given.iter().position(|g| !g) {
+                let names: Vec<&str> = (0..n).filter(|i| missing & (1 << i) != 0).map(|i| spec.criteria[i].id.as_str()).collect();
+                anyhow::bail!("Не задана мера подмножества {:?}", names);
+            }
+        }
+        Capacity::TwoAdditive { shapley, interactions } => {
+            // переход к представлению Мёбиуса: m_i = φ_i − ½·Σ_j I_ij, m_ij = I_ij
+            let mut pair = vec![vec![0.0; n]; n];
+            for inter in interactions {
+                if inter.criteria.len() != 2 {
+                    anyhow::bail!("Индекс взаимодействия задаётся для пары критериев");
+                }
+                let (a, b) = (index(&inter.criteria[0])?, index(&inter.criteria[1])?);
+                pair[a][b] = inter.value;
+                pair[b][a] = inter.value;
+            }
+            let mut single = vec![0.0; n];
+            for (i, crit) in spec.criteria.iter().enumerate() {
+                let phi = shapley.get(&crit.id)
+                    .ok_or_else(|| anyhow::anyhow!("Не задан индекс Шепли для критерия {}", crit.id))?;
+                single[i] = phi - 0.5 * pair[i].iter().sum::<f64>();
+            }
+            for (m, value) in mu.iter_mut().enumerate() {
+                for i in (0..n).filter(|i| m & (1 << i) != 0) {
+                    *value += single[i];
+                    *value += (i + 1..n).filter(|j| m & (1 << j) != 0).map(|j| pair[i][j]).sum::<f64>();
+                }
+            }
+        }
+    }
+
+    if (mu[full] - 1.0).abs() > 1e-6 {
+        anyhow::bail!("Мера всего множества критериев должна быть равна 1 (получено {:.4})", mu[full]);
+    }
+    for m in 0..=full {
+        for i in (0..n).filter(|i| m & (1 << i) == 0) {
+            if mu[m | (1 << i)] < mu[m] - 1e-9 {
+                anyhow::bail!("Ёмкость не монотонна: добавление критерия {} уменьшает меру", spec.criteria[i].id);
+            }
+        }
+    }
+    Ok(mu)
+}
+
+
+/// Интеграл Шоке: Σ (x_(i) − x_(i−1))·μ(A_(i)), значения по возрастанию, A_(i) — критерии с x ≥ x_(i)
+fn choquet(x: &[f64], mu: &[f64]) -> f64 {
+    let mut order: Vec<usize> = (0..x.len()).collect();
+    order.sort_by(|&a, &b| x[a].total_cmp(&x[b]));
+    let mut set = (1usize << x.len()) - 1;
+    let mut prev = 0.0;
+    let mut total = 0.0;
+    for i in order {
+        total += (x[i] - prev) * mu[set];
+        prev = x[i];
+        set &= !(1 << i);
+    }
+    total
+}
+
+
+fn factorial(k: usize) -> f64 {
+    (1..=k).map(|i| i as f64).product()
+}
+
+
+/// φ_i = Σ_{S ⊆ N\i} (n−|S|−1)!·|S|!/n! · [μ(S ∪ i) − μ(S)]
+fn shapley_values(mu: &[f64], n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| {
+            (0..mu.len())
+                .filter(|s| s & (1 << i) == 0)
+                .map(|s| {
+                    let size = s.count_ones() as usize;
+                    factorial(n - size - 1) * factorial(size) / factorial(n) * (mu[s | (1 << i)] - mu[s])
+                })
+                .sum()
+        })
+        .collect()
+}
+
+
+/// I_ij = Σ_{S ⊆ N\{i,j}} (n−|S|−2)!·|S|!/(n−1)! · [μ(S ∪ ij) − μ(S ∪ i) − μ(S ∪ j) + μ(S)]
+fn interaction_indices(mu: &[f64], n: usize) -> Vec<Vec<f64>> {
+    let index = |i: usize, j: usize| -> f64 {
+        let (bi, bj) = (1 << i, 1 << j);
+        (0..mu.len())
+            .filter(|s| s & (bi | bj) == 0)
+            .map(|s| {
+                let size = s.count_ones() as usize;
+                factorial(n - size - 2) * factorial(size) / factorial(n - 1)
+                    * (mu[s | bi | bj] - mu[s | bi] - mu[s | bj] + mu[s])
+            })
+            .sum()
+    };
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 0.0 } else { index(i, j) }).collect())
+        .collect()
+}
+
+
+/// Оператор OWA: значения по убыванию, w_1 — вес наибольшего.
+/// (0,…,0,1) — Вальд, (1,0,…,0) — максимакс, (α,0,…,0,1−α) — Гурвиц, (1/n,…,1/n) — Лаплас.
+pub fn owa(values: &[f64], order_weights: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    sorted.iter().zip(order_weights.iter()).map(|(v, w)| v * w).sum()
+}
+
+
+/// Взвешенный OWA (Торра): учитывает и порядковые веса w, и важность p позиций исходного вектора
+/// (вероятности состояний). Веса ω_i = w*(Σ_{j≤i} p_σ(j)) − w*(Σ_{j<i} p_σ(j)), где w* — кусочно-линейная
+/// функция через точки (k/n, w_1 + … + w_k).
+pub fn wowa(values: &[f64], importance: &[f64], order_weights: &[f64]) -> f64 {
+    let n = values.len();
+    let p_total: f64 = importance.iter().sum();
+    let w_star = |t: f64| {
+        let pos = (t * n as f64).clamp(0.0, n as f64);
+        let k = (pos.floor() as usize).min(n - 1);
+        let before: f64 = order_weights[..k].iter().sum();
+        before + order_weights[k] * (pos - k as f64)
+    };
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    let mut cumulative = 0.0;
+    let mut total = 0.0;
+    for i in order {
+        let next = cumulative + importance[i] / p_total;
+        total += (w_star(next) - w_star(cumulative)) * values[i];
+        cumulative = next;
+    }
+    total
+}
+
+
+/// Степень «оптимистичности» порядковых весов: 1 — максимакс, 0 — Вальд, 0.5 — Лаплас
+pub fn orness(order_weights: &[f64]) -> f64 {
+    let n = order_weights.len();
+    if n < 2 {
+        return 0.5;
+    }
+    order_weights.iter().enumerate().map(|(i, w)| (n - 1 - i) as f64 * w).sum::<f64>() / (n - 1) as f64
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ёмкость по битовым маскам: аддитивная с весами w
+    fn additive(w: &[f64]) -> Vec<f64> {
+        (0..1usize << w.len())
+            .map(|m| (0..w.len()).filter(|i| m & (1 << i) != 0).map(|i| w[i]).sum())
+            .collect()
+    }
+
+    #[test]
+    fn choquet_with_additive_capacity_is_weighted_sum() {
+        let w = [0.5, 0.3, 0.2];
+        let mu = additive(&w);
+        let x = [0.2, 0.9, 0.5];
+        assert!((choquet(&x, &mu) - 0.47).abs() < 1e-12);
+        // для аддитивной меры индексы Шепли — веса, взаимодействия нет
+        let shapley = shapley_values(&mu, 3);
+        for (phi, wi) in shapley.iter().zip(w) {
+            assert!((phi - wi).abs() < 1e-12);
+        }
+        assert!(interaction_indices(&mu, 3).iter().flatten().all(|v| v.abs() < 1e-12));
+    }
+
+    #[test]
+    fn shapley_values_sum_to_one() {
+        // маски: 1 = {0}, 2 = {1}, 3 = {0,1}, 4 = {2}, 5 = {0,2}, 6 = {1,2}, 7 = все
+        let mu = [0.0, 0.3, 0.3, 0.8, 0.1, 0.4, 0.4, 1.0];
+        let shapley = shapley_values(&mu, 3);
+        assert!((shapley.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        // для двух критериев I_12 = μ(12) − μ(1) − μ(2)
+        let pair = [0.0, 0.3, 0.4, 1.0];
+        assert!((interaction_indices(&pair, 2)[0][1] - 0.3).abs() < 1e-12);
+        // и min-оператор (μ = 0 кроме N) — равная важность
+        let min = [0.0, 0.0, 0.0, 1.0];
+        assert!((choquet(&[0.7, 0.4], &min) - 0.4).abs() < 1e-12);
+        assert!(shapley_values(&min, 2).iter().all(|phi| (phi - 0.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn owa_orness() {
+        assert_eq!(orness(&[0.0, 0.0, 1.0]), 0.0);
+        assert_eq!(orness(&[1.0, 0.0, 0.0]), 1.0);
+        assert!((orness(&[1.0 / 3.0; 3]) - 0.5).abs() < 1e-12);
+        let values = [3.0, 1.0, 2.0];
+        assert_eq!(owa(&values, &[0.0, 0.0, 1.0]), 1.0);
+        assert_eq!(owa(&values, &[1.0, 0.0, 0.0]), 3.0);
+    }
+
+    #[test]
+    fn wowa_reduces_to_owa_and_weighted_mean() {
+        let values = [3.0, 1.0, 2.0];
+        let w = [0.5, 0.3, 0.2];
+        // равная важность позиций — обычный OWA: 3·0.5 + 2·0.3 + 1·0.2
+        assert!((wowa(&values, &[1.0, 1.0, 1.0], &w) - 2.3).abs() < 1e-12);
+        assert!((owa(&values, &w) - 2.3).abs() < 1e-12);
+        // равные порядковые веса — взвешенное среднее по важности
+        assert!((wowa(&values, &[0.5, 0.25, 0.25], &[1.0 / 3.0; 3]) - 2.25).abs() < 1e-12);
+    }
+}
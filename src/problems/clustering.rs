@@ -20,8 +20,8 @@ struct ClusteringInput {
 pub struct ClusteringSolver;
 
 impl ClusteringSolver {
-    /// python_path: Some("/path/to/venv/bin/python") или None -> "python3"
-    pub fn solve(python_path: Option<&str>) -> Result<DecisionResult> {
+    /// Дендрограмма рисуется интерпретатором из src/tools/lvenv/bin/python3
+    pub fn solve() -> Result<DecisionResult> {
         // read input file
         let mut in_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         in_path.push("data/data_for_clustering.json");
@@ -42,11 +42,11 @@ impl ClusteringSolver {
 
         // попарные евклидовы расстояния
         let mut dist = vec![vec![0.0_f64; n]; n];
-        for i in 0..n {
-            for j in (i + 1)..n {
-                let d = input.objects[i].features.iter()
-                    .zip(input.objects[j].features.iter())
-                    .map(|(a, b)| (a - b).powi(2))
+        for (i, a) in input.objects.iter().enumerate() {
+            for (j, b) in input.objects.iter().enumerate().skip(i + 1) {
+                let d = a.features.iter()
+                    .zip(b.features.iter())
+                    .map(|(x, y)| (x - y).powi(2))
                     .sum::<f64>()
                     .sqrt();
                 dist[i][j] = d;
@@ -164,6 +164,7 @@ impl ClusteringSolver {
             chosen,
             scores,
             method: "clustering_single_linkage".to_string(),
+            ..Default::default()
         })
     }
 }
@@ -0,0 +1,186 @@
+// -----------------------------
+// src/problems/criteria_consensus.rs
+// -----------------------------
+//! Сравнение всех применимых критериев на одной задаче и согласованное решение.
+//!
+//! Запускаются критерии `DecisionRulesSolver` (для owa и wowa нужны порядковые веса в задаче)
+//! и, если заданы вероятности состояний, критерии `ProbabilisticRulesSolver` — с параметрами
+//! по умолчанию. По местам альтернатив в каждом критерии (при равенстве оценок — среднее место)
+//! строятся три сводки:
+//! - Борда: сумма очков n − место;
+//! - Копленд: число побед минус число поражений в попарных сравнениях по большинству критериев;
+//! - частота: сколько раз альтернатива оказалась среди выбранных.
+//!
+//! Итоговое решение — победитель по Борда; совпадение с остальными сводками отмечается в примечаниях.
+use crate::core::base::*;
+use crate::problems::bayes_rules::ProbabilisticRulesSolver;
+use crate::problems::decision_rules::DecisionRulesSolver;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+const EPS: f64 = 1e-9;
+
+const DECISION_CRITERIA: &[&str] = &[
+    "wald", "maximax", "hurwicz", "savidge", "laplace", "product", "hurwicz-regret", "extended-minimax",
+];
+const ORDER_WEIGHTED_CRITERIA: &[&str] = &["owa", "wowa"];
+const PROBABILISTIC_CRITERIA: &[&str] = &[
+    "bayes", "ferstner", "hodge-lehman", "hermeyer", "bayes-regret", "minimax-regret-p",
+];
+
+
+pub struct CriteriaConsensusSolver;
+
+
+impl CriteriaConsensusSolver {
+    pub fn solve(spec: &ProblemSpec) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        if spec.alternatives.is_empty() {
+            anyhow::bail!("Empty problem");
+        }
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+        let n = ids.len();
+        let mut notes = Vec::new();
+        if spec.orientation == Orientation::Loss {
+            notes.push("Значения матрицы — потери: во всех критериях лучшее значение — наименьшее".to_string());
+        }
+
+        let mut criteria: Vec<&str> = DECISION_CRITERIA.to_vec();
+        if spec.order_weights.is_some() {
+            criteria.extend(ORDER_WEIGHTED_CRITERIA);
+        }
+        let with_probabilities = spec.state_probabilities.as_ref().is_some_and(|p| p.len() == spec.criteria.len());
+        if with_probabilities {
+            criteria.extend(PROBABILISTIC_CRITERIA);
+        } else {
+            notes.push("Вероятности состояний не заданы — вероятностные критерии пропущены".to_string());
+        }
+
+        let mut results = Vec::new();
+        for &criterion in &criteria {
+            let result = if PROBABILISTIC_CRITERIA.contains(&criterion) {
+                ProbabilisticRulesSolver::solve(spec, criterion, None)?
+            } else {
+                DecisionRulesSolver::solve(spec, criterion, None)?
+            };
+            results.push(result);
+        }
+
+        // scores[c][i] и места ranks[c][i] альтернативы i по критерию c (1 — лучшая)
+        let scores: Vec<Vec<f64>> = results.iter()
+            .map(|r| ids.iter().map(|id| r.scores.iter().find(|(a, _)| a == id).map_or(f64::NAN, |(_, v)| *v)).collect())
+            .collect();
+        let ranks: Vec<Vec<f64>> = results.iter().map(|r| places(&r.scores, &ids)).collect();
+
+        let borda: Vec<f64> = (0..n).map(|i| ranks.iter().map(|r| n as f64 - r[i]).sum()).collect();
+        let frequency: Vec<f64> = ids.iter()
+            .map(|id| results.iter().filter(|r| r.chosen.contains(id)).count() as f64)
+            .collect();
+        // Копленд: a побеждает b, если a выше b по большинству критериев
+        let copeland: Vec<f64> = (0..n)
+            .map(|a| {
+                (0..n).filter(|&b| b != a).map(|b| {
+                    let wins = ranks.iter().filter(|r| r[a] < r[b]).count();
+                    let losses = ranks.iter().filter(|r| r[b] < r[a]).count();
+                    (wins as f64 - losses as f64).signum()
+                }).sum()
+            })
+            .collect();
+
+        for (criterion, result) in criteria.iter().zip(results.iter()) {
+            notes.push(format!("{}: {}", criterion, result.chosen.join(", ")));
+        }
+        let winners = |values: &[f64]| -> Vec<String> {
+            let top = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            (0..n).filter(|&i| (values[i] - top).abs() < EPS).map(|i| ids[i].clone()).collect()
+        };
+        let (by_borda, by_copeland, by_frequency) = (winners(&borda), winners(&copeland), winners(&frequency));
+        notes.push(format!("Победитель по Копленду: {}", by_copeland.join(", ")));
+        notes.push(format!("Чаще всего выбирается: {}", by_frequency.join(", ")));
+        notes.push(if by_borda == by_copeland && by_borda == by_frequency {
+            "Борда, Копленд и частота выбора согласованы".to_string()
+        } else {
+            "Сводки расходятся: решение зависит от способа согласования критериев".to_string()
+        });
+
+        let columns: Vec<String> = criteria.iter().map(|c| c.to_string()).collect();
+        let by_alternative = |matrix: &[Vec<f64>]| -> Vec<(String, Vec<f64>)> {
+            ids.iter().enumerate().map(|(i, id)| (id.clone(), matrix.iter().map(|row| row[i]).collect())).collect()
+        };
+        let tables = vec![
+            ResultTable { title: "Оценки по критериям".to_string(), columns: columns.clone(), rows: by_alternative(&scores) },
+            ResultTable { title: "Места по критериям (1 — лучшая)".to_string(), columns, rows: by_alternative(&ranks) },
+            ResultTable {
+                title: "Согласование".to_string(),
+                columns: vec!["Борда".to_string(), "Копленд".to_string(), "Выбрана (раз)".to_string()],
+                rows: (0..n).map(|i| (ids[i].clone(), vec![borda[i], copeland[i], frequency[i]])).collect(),
+            },
+        ];
+
+        let mut consensus: Vec<(String, f64)> = ids.iter().cloned().zip(borda.iter().cloned()).collect();
+        consensus.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Ok(DecisionResult {
+            chosen: by_borda,
+            scores: consensus,
+            method: format!("compare-all ({} критериев, консенсус по Борда)", criteria.len()),
+            tables,
+            notes,
+            ..Default::default()
+        })
+    }
+}
+
+
+/// Места альтернатив по оценкам, упорядоченным решателем от лучшей к худшей;
+/// равные оценки делят среднее место
+fn places(sorted: &[(String, f64)], ids: &[String]) -> Vec<f64> {
+    let mut place = vec![f64::NAN; ids.len()];
+    let mut start = 0;
+    while start < sorted.len() {
+        let end = (start..sorted.len()).find(|&k| (sorted[k].1 - sorted[start].1).abs() > EPS).unwrap_or(sorted.len());
+        let shared = (start + end + 1) as f64 / 2.0;
+        for (id, _) in &sorted[start..end] {
+            if let Some(i) = ids.iter().position(|x| x == id) {
+                place[i] = shared;
+            }
+        }
+        start = end;
+    }
+    place
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_share_average_rank_on_ties() {
+        let ids: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+        let sorted = vec![("a".to_string(), 3.0), ("c".to_string(), 2.0), ("b".to_string(), 2.0), ("d".to_string(), 1.0)];
+        assert_eq!(places(&sorted, &ids), vec![1.0, 2.5, 2.5, 4.0]);
+    }
+
+    #[test]
+    fn borda_and_copeland_for_dominating_alternative() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "s1" }, { "id": "s2" }],
+            "alternatives": [
+                { "id": "A", "values": [3, 3] },
+                { "id": "B", "values": [2, 2] },
+                { "id": "C", "values": [1, 1] }
+            ]
+        }"#).unwrap();
+        let result = CriteriaConsensusSolver::solve(&spec).unwrap();
+        // 8 критериев без вероятностей; в extended-minimax у B и C вероятность 0 — общее место 2.5
+        assert_eq!(result.tables[1].columns.len(), 8);
+        let summary = &result.tables[2].rows;
+        // Борда: A = 8·2, B = 7·1 + 0.5, C = 0.5; Копленд: +2, 0, −2; A выбрана всеми критериями
+        assert_eq!(summary[0].1, vec![16.0, 2.0, 8.0]);
+        assert_eq!(summary[1].1, vec![7.5, 0.0, 0.0]);
+        assert_eq!(summary[2].1, vec![0.5, -2.0, 0.0]);
+        assert_eq!(result.chosen, vec!["A"]);
+        assert!(result.notes.contains(&"Борда, Копленд и частота выбора согласованы".to_string()));
+    }
+}
@@ -1,65 +1,121 @@
-use crate::core::base::{ProblemSpec, DecisionResult};
+use crate::core::base::{ProblemSpec, DecisionResult, Orientation, ResultTable};
+use crate::problems::choquet::{orness, owa, wowa};
+use crate::problems::missing::ensure_complete;
 use anyhow::Result;
+use good_lp::{constraint, default_solver, variable, variables, Expression, Solution, SolverModel};
 
 pub struct DecisionRulesSolver;
 
 impl DecisionRulesSolver {
-    /// criterion: "wald" | "maximax" | "hurwicz" | "savidge" | "laplace"
+    /// criterion: "wald" | "maximax" | "hurwicz" | "savidge" | "laplace" | "owa" | "wowa"
+    /// | "product" | "hurwicz-regret" | "extended-minimax"
+    /// Для owa/wowa порядковые веса берутся из spec.order_weights, а если их нет —
+    /// строятся как у Гурвица: (α, 0, …, 0, 1 − α).
+    /// hurwicz-regret — Гурвиц на матрице сожалений: α·min_j r_ij + (1 − α)·max_j r_ij → min.
+    /// extended-minimax — расширенный минимакс: оптимальная смешанная стратегия ЛПР
+    /// в игре с природой, max_q min_j Σ_i q_i·a_ij (решается через `good_lp`).
+    /// Если spec.orientation = loss, формулы зеркальны: «лучшее» значение — минимальное,
+    /// «худшее» — максимальное, сожаления r_ij = a_ij − min_k a_kj, оценки минимизируются.
     pub fn solve(spec: &ProblemSpec, criterion: &str, hurwicz_alpha: Option<f64>) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
         if spec.alternatives.is_empty() || spec.criteria.is_empty() {
             anyhow::bail!("Empty problem");
         }
 
         let n_alt = spec.alternatives.len();
         let n_crit = spec.criteria.len();
+        if spec.alternatives.iter().any(|a| a.values.len() != n_crit) {
+            anyhow::bail!("Alternative values length must equal number of criteria");
+        }
 
         // собираем матрицу: rows = альтернативы, cols = критерии
         let matrix: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.clone()).collect();
 
+        let orientation = spec.orientation;
         let mut scores: Vec<(String, f64)> = Vec::new();
+        let mut notes = Vec::new();
+        let mut method = criterion.to_string();
+        // оценки в единицах потерь (потери матрицы или сожаления) минимизируются
+        let mut minimize = orientation == Orientation::Loss;
+        if minimize {
+            notes.push("Значения матрицы — потери: лучшее значение — наименьшее".to_string());
+        }
 
         match criterion {
             "wald" => {
-                // минимакс (по каждой альтернативе берём минимум по столбцам)
+                // максимин (по каждой альтернативе берём худший результат по столбцам)
                 for (i, alt) in spec.alternatives.iter().enumerate() {
-                    let min_val = matrix[i].iter().cloned().fold(f64::INFINITY, f64::min);
-                    scores.push((alt.id.clone(), min_val));
+                    scores.push((alt.id.clone(), orientation.worst(&matrix[i])));
                 }
             },
             "maximax" => {
-                // максимакс (по каждой альтернативе берём максимум по столбцам)
+                // максимакс (по каждой альтернативе берём лучший результат по столбцам)
                 for (i, alt) in spec.alternatives.iter().enumerate() {
-                    let max_val = matrix[i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-                    scores.push((alt.id.clone(), max_val));
+                    scores.push((alt.id.clone(), orientation.best(&matrix[i])));
                 }
             },
             "hurwicz" => {
                 let alpha = hurwicz_alpha.unwrap_or(0.5);
+                // α — коэффициент оптимизма при лучшем результате
                 for (i, alt) in spec.alternatives.iter().enumerate() {
-                    let min_val = matrix[i].iter().cloned().fold(f64::INFINITY, f64::min);
-                    let max_val = matrix[i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-                    let score = alpha * max_val + (1.0 - alpha) * min_val;
+                    let score = alpha * orientation.best(&matrix[i]) + (1.0 - alpha) * orientation.worst(&matrix[i]);
                     scores.push((alt.id.clone(), score));
                 }
             },
             "savidge" => {
                 // критерий Сэвиджа-Нигана: строим матрицу потерь (сожалений)
-                let mut loss_matrix: Vec<Vec<f64>> = vec![vec![0.0; n_crit]; n_alt];
-                for j in 0..n_crit {
-                    let col_max = (0..n_alt).map(|i| matrix[i][j]).fold(f64::NEG_INFINITY, f64::max);
-                    for i in 0..n_alt {
-                        loss_matrix[i][j] = col_max - matrix[i][j];
-                    }
-                }
+                let loss_matrix = regret_matrix(&matrix, orientation);
                 for (i, alt) in spec.alternatives.iter().enumerate() {
                     let max_loss = loss_matrix[i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
                     scores.push((alt.id.clone(), max_loss));
                 }
                 // у Сэвиджа минимизируем потери
-                scores.sort_by(|a,b| a.1.partial_cmp(&b.1).unwrap());
-                let best_val = scores[0].1;
-                let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best_val).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
-                return Ok(DecisionResult { chosen, scores, method: "savidge".to_string() });
+                minimize = true;
+            },
+            "hurwicz-regret" => {
+                let alpha = hurwicz_alpha.unwrap_or(0.5);
+                let loss_matrix = regret_matrix(&matrix, orientation);
+                for (i, alt) in spec.alternatives.iter().enumerate() {
+                    let min_loss = loss_matrix[i].iter().cloned().fold(f64::INFINITY, f64::min);
+                    let max_loss = loss_matrix[i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                    scores.push((alt.id.clone(), alpha * min_loss + (1.0 - alpha) * max_loss));
+                }
+                minimize = true;
+            },
+            "product" => {
+                // критерий произведений определён для положительных значений, иначе матрица сдвигается;
+                // для потерь произведение минимизируется
+                let min = matrix.iter().flatten().cloned().fold(f64::INFINITY, f64::min);
+                let shift = if min <= 0.0 { 1.0 - min } else { 0.0 };
+                if shift > 0.0 {
+                    notes.push(format!("Для критерия произведений ко всем значениям прибавлено {} (минимальное значение {})", shift, min));
+                }
+                for (i, alt) in spec.alternatives.iter().enumerate() {
+                    scores.push((alt.id.clone(), matrix[i].iter().map(|v| v + shift).product()));
+                }
+            },
+            "extended-minimax" => {
+                // для потерь игра решается на выплатах −a_ij
+                let sign = orientation.sign();
+                let gains: Vec<Vec<f64>> = matrix.iter().map(|row| row.iter().map(|v| sign * v).collect()).collect();
+                let (strategy, value) = mixed_maximin(&gains)?;
+                let value = sign * value;
+                let worst: Vec<f64> = matrix.iter().map(|row| orientation.worst(row)).collect();
+                let pure = orientation.best(&worst);
+                notes.push(format!("Цена игры с природой: {:.4} (гарантированный результат чистой стратегии по Вальду: {:.4})", value, pure));
+                let guaranteed: Vec<f64> = (0..n_crit).map(|j| (0..n_alt).map(|i| strategy[i] * matrix[i][j]).sum()).collect();
+                let table = ResultTable {
+                    title: "Ожидаемый результат смешанной стратегии по состояниям".to_string(),
+                    columns: spec.criteria.iter().map(|c| c.id.clone()).collect(),
+                    rows: vec![("Σ q_i·a_ij".to_string(), guaranteed)],
+                };
+                // оценка альтернативы — её вероятность в оптимальной смешанной стратегии
+                for (alt, q) in spec.alternatives.iter().zip(strategy) {
+                    scores.push((alt.id.clone(), q));
+                }
+                scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+                let chosen: Vec<String> = scores.iter().filter(|(_,q)| *q > 1e-9).map(|(id,_)| id.clone()).collect();
+                return Ok(DecisionResult { chosen, scores, method, tables: vec![table], notes, ..Default::default() });
             },
             "laplace" => {
                 // усредняем по всем столбцам
@@ -68,14 +124,133 @@ impl DecisionRulesSolver {
                     scores.push((alt.id.clone(), avg));
                 }
             },
+            "owa" | "wowa" => {
+                let order_weights = match &spec.order_weights {
+                    Some(w) => {
+                        if w.len() != n_crit { anyhow::bail!("Order weights length must equal number of columns") }
+                        let total: f64 = w.iter().sum();
+                        w.iter().map(|x| x / total).collect()
+                    },
+                    None => {
+                        let alpha = hurwicz_alpha.unwrap_or(0.5);
+                        let mut w = vec![0.0; n_crit];
+                        w[0] += alpha;
+                        w[n_crit - 1] += 1.0 - alpha;
+                        w
+                    },
+                };
+                // для взвешенного OWA важность столбцов — вероятности состояний, иначе веса критериев
+                let importance: Vec<f64> = match &spec.state_probabilities {
+                    Some(p) if p.len() == n_crit => p.clone(),
+                    _ => spec.criteria.iter().map(|c| c.weight.unwrap_or(1.0)).collect(),
+                };
+                // w_1 относится к лучшему значению: для потерь OWA считается по −a_ij
+                let sign = orientation.sign();
+                for (i, alt) in spec.alternatives.iter().enumerate() {
+                    let row: Vec<f64> = matrix[i].iter().map(|v| sign * v).collect();
+                    let score = if criterion == "owa" {
+                        owa(&row, &order_weights)
+                    } else {
+                        wowa(&row, &importance, &order_weights)
+                    };
+                    scores.push((alt.id.clone(), sign * score));
+                }
+                method = format!("{} (orness={:.3})", criterion, orness(&order_weights));
+            },
             _ => anyhow::bail!("Unknown criterion: {}", criterion)
         }
 
-        // для остальных критериев выбираем максимум
-        scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+        // выбираем максимум (для потерь — минимум)
+        if minimize {
+            scores.sort_by(|a,b| a.1.total_cmp(&b.1));
+        } else {
+            scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        }
         let best_val = scores[0].1;
         let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best_val).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
 
-        Ok(DecisionResult { chosen, scores, method: criterion.to_string() })
+        Ok(DecisionResult { chosen, scores, method, notes, ..Default::default() })
+    }
+}
+
+
+/// Матрица сожалений (рисков Сэвиджа) — отставание от лучшего значения в столбце:
+/// r_ij = max_k a_kj − a_ij для выплат и r_ij = a_ij − min_k a_kj для потерь
+pub fn regret_matrix(matrix: &[Vec<f64>], orientation: Orientation) -> Vec<Vec<f64>> {
+    let n_crit = matrix.first().map_or(0, |row| row.len());
+    let col_best: Vec<f64> = (0..n_crit)
+        .map(|j| orientation.best(&matrix.iter().map(|row| row[j]).collect::<Vec<f64>>()))
+        .collect();
+    let sign = orientation.sign();
+    matrix.iter()
+        .map(|row| row.iter().zip(col_best.iter()).map(|(v, b)| sign * (b - v)).collect())
+        .collect()
+}
+
+
+/// Оптимальная смешанная стратегия строк и цена игры: max v при Σ_i q_i·a_ij ≥ v для всех j, Σ q_i = 1, q ≥ 0
+fn mixed_maximin(matrix: &[Vec<f64>]) -> Result<(Vec<f64>, f64)> {
+    let n_crit = matrix[0].len();
+    let mut vars = variables!();
+    let q: Vec<_> = matrix.iter().map(|_| vars.add(variable().min(0.0))).collect();
+    let v = vars.add(variable());
+
+    let mut model = vars.maximise(v).using(default_solver);
+    let total: Expression = q.iter().sum();
+    model = model.with(constraint!(total == 1.0));
+    for j in 0..n_crit {
+        let expected: Expression = q.iter().zip(matrix.iter()).map(|(qi, row)| row[j] * *qi).sum();
+        model = model.with(constraint!(expected >= v));
+    }
+    let solution = model.solve()?;
+    Ok((q.iter().map(|qi| solution.value(*qi).max(0.0)).collect(), solution.value(v)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_maximin_solves_two_by_two_game() {
+        // 4q + 2(1 − q) = q + 3(1 − q) → q = 1/4, цена игры 2.5
+        let (strategy, value) = mixed_maximin(&[vec![4.0, 1.0], vec![2.0, 3.0]]).unwrap();
+        assert!((strategy[0] - 0.25).abs() < 1e-6);
+        assert!((strategy[1] - 0.75).abs() < 1e-6);
+        assert!((value - 2.5).abs() < 1e-6);
+        // при седловой точке оптимальна чистая стратегия
+        let (strategy, value) = mixed_maximin(&[vec![3.0, 2.0], vec![1.0, 0.0]]).unwrap();
+        assert!((strategy[0] - 1.0).abs() < 1e-6);
+        assert!((value - 2.0).abs() < 1e-6);
+    }
+    #[test]
+    fn loss_orientation_mirrors_gain_rules() {
+        let spec = |orientation: &str, rows: [[f64; 2]; 2]| -> ProblemSpec {
+            serde_json::from_str(&format!(r#"{{
+                "criteria": [{{ "id": "s1" }}, {{ "id": "s2" }}],
+                "alternatives": [{{ "id": "A1", "values": {:?} }}, {{ "id": "A2", "values": {:?} }}],
+                "orientation": "{}"
+            }}"#, rows[0], rows[1], orientation)).unwrap()
+        };
+        let loss = spec("loss", [[0.0, 3.0], [1.0, 0.0]]);
+        let gain = spec("gain", [[0.0, -3.0], [-1.0, 0.0]]);
+
+        // Вальд для потерь: min_i max_j a_ij = 1 у A2
+        let wald = DecisionRulesSolver::solve(&loss, "wald", None).unwrap();
+        assert_eq!(wald.chosen, vec!["A2"]);
+        assert!((wald.scores[0].1 - 1.0).abs() < 1e-12);
+        assert_eq!(DecisionRulesSolver::solve(&gain, "wald", None).unwrap().chosen, wald.chosen);
+
+        // сожаления a_ij − min_k a_kj: A1 = (0, 3), A2 = (1, 0) → Сэвидж выбирает A2
+        let savage = DecisionRulesSolver::solve(&loss, "savidge", None).unwrap();
+        assert_eq!(savage.chosen, vec!["A2"]);
+        assert!((savage.scores[0].1 - 1.0).abs() < 1e-12);
+
+        // игра на −a_ij: q = (1/4, 3/4), цена игры в потерях 0.75
+        let mixed = DecisionRulesSolver::solve(&loss, "extended-minimax", None).unwrap();
+        let q = |id: &str| mixed.scores.iter().find(|(a, _)| a == id).unwrap().1;
+        assert!((q("A1") - 0.25).abs() < 1e-6);
+        assert!((q("A2") - 0.75).abs() < 1e-6);
+        assert!(mixed.notes.iter().any(|n| n.contains("Цена игры с природой: 0.7500")));
     }
 }
@@ -0,0 +1,408 @@
+// -----------------------------
+// src/problems/decision_tree.rs
+// -----------------------------
+//! Многоэтапные деревья решений.
+//!
+//! Дерево задаётся в JSON узлами трёх типов:
+//! - decision — узел решения, ветви — варианты действий (с затратами `cost`);
+//! - chance — узел случая, ветви — исходы с вероятностями `probability` (и, возможно, затратами);
+//! - terminal — конечный узел с выплатой `payoff`.
+//!
+//! Свёртка (обратная индукция) идёт от листьев к корню. Значение узла — итоговый результат
+//! с учётом всех затрат на пути от корня: в листе это выплата минус накопленные затраты
+//! (или её полезность), в узле случая — оценка распределения значений потомков
+//! (по умолчанию Байес, можно задать `criterion` "ferstner" или "hodge-lehman" из `bayes_rules`),
+//! в узле решения — максимум по ветвям.
+//!
+//! Режимы: ожидаемое значение (ev) или ожидаемая полезность (utility) с функцией `utility`
+//! из MAUT; для полезности выводится детерминированный эквивалент.
+//! Решённое дерево выводится текстом и может быть сохранено в формате Graphviz DOT.
+use crate::core::base::*;
+use crate::problems::bayes_rules::criterion_score;
+use crate::problems::maut::{certainty_equivalent, utility};
+use anyhow::Result;
+use serde::Deserialize;
+use std::fs;
+
+
+#[derive(Debug, Deserialize)]
+struct TreeInput {
+    root: Node,
+    // функция полезности для режима utility; диапазон по умолчанию — от худшего до лучшего исхода
+    utility: Option<UtilityFunction>,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Node {
+    Decision {
+        id: Option<String>,
+        branches: Vec<Branch>,
+    },
+    Chance {
+        id: Option<String>,
+        branches: Vec<Branch>,
+        criterion: Option<String>,
+        lambda: Option<f64>,
+    },
+    Terminal {
+        id: Option<String>,
+        payoff: f64,
+    },
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Branch {
+    label: String,
+    #[serde(default)]
+    cost: f64,
+    probability: Option<f64>,
+    node: Node,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeMode {
+    ExpectedValue,
+    ExpectedUtility,
+}
+
+
+impl TreeMode {
+    /// "ev" | "utility"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "ev" => Ok(TreeMode::ExpectedValue),
+            "utility" => Ok(TreeMode::ExpectedUtility),
+            _ => anyhow::bail!("Unknown tree rollback mode: {}", name),
+        }
+    }
+}
+
+
+/// Решённое дерево: результат для печати, текстовое представление и описание в формате DOT
+pub struct SolvedTree {
+    pub result: DecisionResult,
+    pub text: String,
+    pub dot: String,
+}
+
+
+pub struct DecisionTreeSolver;
+
+
+impl DecisionTreeSolver {
+    pub fn solve(input_path: &str, mode: TreeMode) -> Result<SolvedTree> {
+        let s = fs::read_to_string(input_path)?;
+        let input: TreeInput = serde_json::from_str(&s)?;
+
+        let mut outcomes = Vec::new();
+        collect_outcomes(&input.root, 0.0, &mut outcomes);
+        let lo = outcomes.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = outcomes.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let func = match mode {
+            TreeMode::ExpectedValue => None,
+            TreeMode::ExpectedUtility => Some(input.utility.as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Utility function required for expected utility rollback"))?),
+        };
+        let value_of = |x: f64| match func {
+            Some(f) => utility(f, x, lo, hi, true),
+            None => Ok(x),
+        };
+
+        let mut counters = [0usize; 3];
+        let root = rollback(&input.root, 0.0, &value_of, &mut counters)?;
+        let mut ids = Vec::new();
+        collect_ids(&root, &mut ids);
+        ids.sort();
+        if let Some(w) = ids.windows(2).find(|w| w[0] == w[1]) {
+            anyhow::bail!("Идентификатор узла {} встречается несколько раз", w[0]);
+        }
+
+        let (mut scores, chosen) = match &root.kind {
+            Kind::Decision(best) => (
+                root.children.iter().map(|(b, child)| (b.label.clone(), child.value)).collect::<Vec<(String, f64)>>(),
+                vec![root.children[*best].0.label.clone()],
+            ),
+            _ => (vec![(root.id.clone(), root.value)], vec![root.id.clone()]),
+        };
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+
+        let mut notes = Vec::new();
+        policy(&root, &mut notes);
+        let method = match func {
+            None => "decision_tree_ev".to_string(),
+            Some(f) => {
+                let ce = certainty_equivalent(f, root.value, lo, hi)?;
+                notes.push(format!("Детерминированный эквивалент: {:.4} (диапазон исходов [{}, {}])", ce, lo, hi));
+                "decision_tree_utility".to_string()
+            }
+        };
+
+        let mut text = String::new();
+        render_text(&root, "", &mut text);
+        let mut dot = String::from("digraph decision_tree {\n    rankdir=LR;\n");
+        render_dot(&root, &mut dot);
+        dot.push_str("}\n");
+
+        Ok(SolvedTree {
+            result: DecisionResult { chosen, scores, method, notes, ..Default::default() },
+            text,
+            dot,
+        })
+    }
+}
+
+
+/// Узел после свёртки
+struct Solved<'a> {
+    id: String,
+    kind: Kind,
+    value: f64,
+    children: Vec<(&'a Branch, Solved<'a>)>,
+}
+
+
+enum Kind {
+    // номер оптимальной ветви
+    Decision(usize),
+    Chance,
+    // выплата за вычетом затрат на пути
+    Terminal(f64),
+}
+
+
+fn collect_ids<'a>(node: &'a Solved, out: &mut Vec<&'a str>) {
+    out.push(&node.id);
+    node.children.iter().for_each(|(_, child)| collect_ids(child, out));
+}
+
+
+/// Итоговые результаты всех листьев с учётом накопленных затрат
+fn collect_outcomes(node: &Node, spent: f64, out: &mut Vec<f64>) {
+    match node {
+        Node::Terminal { payoff, .. } => out.push(payoff - spent),
+        Node::Decision { branches, .. } | Node::Chance { branches, .. } => {
+            for b in branches {
+                collect_outcomes(&b.node, spent + b.cost, out);
+            }
+        }
+    }
+}
+
+
+/// Обратная индукция; counters — нумерация безымянных узлов решения, случая и листьев
+fn rollback<'a>(
+    node: &'a Node,
+    spent: f64,
+    value_of: &dyn Fn(f64) -> Result<f64>,
+    counters: &mut [usize; 3],
+) -> Result<Solved<'a>> {
+    match node {
+        Node::Terminal { id, payoff } => {
+            let net = payoff - spent;
+            Ok(Solved { id: node_id(id, counters, 2, "T"), kind: Kind::Terminal(net), value: value_of(net)?, children: Vec::new() })
+        }
+        Node::Decision { id, branches } => {
+            let id = node_id(id, counters, 0, "D");
+            if branches.is_empty() {
+                anyhow::bail!("У узла решения {} нет ветвей", id);
+            }
+            let children = rollback_branches(branches, spent, value_of, counters)?;
+            let best = (0..children.len()).fold(0, |b, i| if children[i].1.value > children[b].1.value + 1e-12 { i } else { b });
+            let value = children[best].1.value;
+            Ok(Solved { id, kind: Kind::Decision(best), value, children })
+        }
+        Node::Chance { id, branches, criterion, lambda } => {
+            let id = node_id(id, counters, 1, "C");
+            if branches.is_empty() {
+                anyhow::bail!("У узла случая {} нет ветвей", id);
+            }
+            let probs: Vec<f64> = branches.iter()
+                .map(|b| b.probability.ok_or_else(|| anyhow::anyhow!("Узел случая {}: не задана вероятность ветви «{}»", id, b.label)))
+                .collect::<Result<_>>()?;
+            if probs.iter().any(|p| *p < 0.0) || (probs.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+                anyhow::bail!("Узел случая {}: вероятности ветвей должны быть неотрицательными и давать в сумме 1", id);
+            }
+            let children = rollback_branches(branches, spent, value_of, counters)?;
+            let values: Vec<f64> = children.iter().map(|(_, c)| c.value).collect();
+            let value = criterion_score(&values, &probs, criterion.as_deref().unwrap_or("bayes"), *lambda, Orientation::Gain)?;
+            Ok(Solved { id, kind: Kind::Chance, value, children })
+        }
+    }
+}
+
+
+fn rollback_branches<'a>(
+    branches: &'a [Branch],
+    spent: f64,
+    value_of: &dyn Fn(f64) -> Result<f64>,
+    counters: &mut [usize; 3],
+) -> Result<Vec<(&'a Branch, Solved<'a>)>> {
+    branches.iter().map(|b| Ok((b, rollback(&b.node, spent + b.cost, value_of, counters)?))).collect()
+}
+
+
+/// Заданный id узла или автоматический (D1, C1, T1, ...)
+fn node_id(id: &Option<String>, counters: &mut [usize; 3], kind: usize, prefix: &str) -> String {
+    counters[kind] += 1;
+    id.clone().unwrap_or_else(|| format!("{}{}", prefix, counters[kind]))
+}
+
+
+/// Оптимальная стратегия: выбор в каждом узле решения, достижимом при этой стратегии
+fn policy(node: &Solved, notes: &mut Vec<String>) {
+    match node.kind {
+        Kind::Decision(best) => {
+            let (branch, child) = &node.children[best];
+            notes.push(format!("{}: выбрать «{}» (значение {:.4})", node.id, branch.label, child.value));
+            policy(child, notes);
+        }
+        Kind::Chance => node.children.iter().for_each(|(_, child)| policy(child, notes)),
+        Kind::Terminal(_) => {}
+    }
+}
+
+
+fn node_title(node: &Solved) -> String {
+    match node.kind {
+        Kind::Decision(_) => format!("[решение] {} = {:.4}", node.id, node.value),
+        Kind::Chance => format!("[случай] {} = {:.4}", node.id, node.value),
+        Kind::Terminal(net) => format!("[исход] {} = {:.4} (результат {})", node.id, node.value, net),
+    }
+}
+
+
+fn branch_title(node: &Solved, index: usize) -> String {
+    let branch = node.children[index].0;
+    let mut title = branch.label.clone();
+    if let Some(p) = branch.probability {
+        title.push_str(&format!(" (p = {})", p));
+    }
+    if branch.cost != 0.0 {
+        title.push_str(&format!(" (затраты {})", branch.cost));
+    }
+    if matches!(node.kind, Kind::Decision(best) if best == index) {
+        title.push_str(" *");
+    }
+    title
+}
+
+
+/// Текстовое дерево; оптимальные ветви узлов решения отмечены «*»
+fn render_text(node: &Solved, indent: &str, out: &mut String) {
+    if indent.is_empty() {
+        out.push_str(&node_title(node));
+        out.push('\n');
+    }
+    for (i, (_, child)) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        out.push_str(&format!("{}{} {} → {}\n", indent, if last { "└──" } else { "├──" }, branch_title(node, i), node_title(child)));
+        render_text(child, &format!("{}{}", indent, if last { "    " } else { "│   " }), out);
+    }
+}
+
+
+/// Узлы и рёбра в формате DOT: решения — прямоугольники, случаи — эллипсы, исходы — без рамки;
+/// оптимальные ветви выделены
+fn render_dot(node: &Solved, out: &mut String) {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let shape = match node.kind {
+        Kind::Decision(_) => "box",
+        Kind::Chance => "ellipse",
+        Kind::Terminal(_) => "plaintext",
+    };
+    out.push_str(&format!("    \"{}\" [shape={}, label=\"{}\\n{:.4}\"];\n", escape(&node.id), shape, escape(&node.id), node.value));
+    for (i, (_, child)) in node.children.iter().enumerate() {
+        let style = if matches!(node.kind, Kind::Decision(best) if best == i) { ", color=red, penwidth=2" } else { "" };
+        out.push_str(&format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"{}];\n",
+            escape(&node.id), escape(&child.id), escape(branch_title(node, i).trim_end_matches(" *")), style
+        ));
+        render_dot(child, out);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve_json(name: &str, json: &str) -> SolvedTree {
+        let path = std::env::temp_dir().join(format!("decision_tree_{}_{}.json", name, std::process::id()));
+        fs::write(&path, json).unwrap();
+        let solved = DecisionTreeSolver::solve(path.to_str().unwrap(), TreeMode::ExpectedValue);
+        fs::remove_file(&path).unwrap();
+        solved.unwrap()
+    }
+
+    const TWO_STAGE: &str = r#"{
+        "root": { "type": "decision", "id": "Старт", "branches": [
+            { "label": "Исследование", "cost": 10, "node": { "type": "chance", "id": "Рынок", "branches": [
+                { "label": "Успех", "probability": 0.6, "node": { "type": "decision", "id": "Завод", "branches": [
+                    { "label": "Большой", "cost": 100, "node": { "type": "chance", "id": "Спрос", "branches": [
+                        { "label": "Высокий", "probability": 0.7, "node": { "type": "terminal", "payoff": 400 } },
+                        { "label": "Низкий", "probability": 0.3, "node": { "type": "terminal", "payoff": 50 } }
+                    ] } },
+                    { "label": "Малый", "cost": 40, "node": { "type": "terminal", "payoff": 150 } }
+                ] } },
+                { "label": "Провал", "probability": 0.4, "node": { "type": "terminal", "payoff": 0 } }
+            ] } },
+            { "label": "Без исследования", "node": { "type": "chance", "id": "Наугад", "branches": [
+                { "label": "Высокий", "probability": 0.5, "node": { "type": "terminal", "payoff": 180 } },
+                { "label": "Низкий", "probability": 0.5, "node": { "type": "terminal", "payoff": 20 } }
+            ] } }
+        ] }
+    }"#;
+
+    #[test]
+    fn two_stage_rollback_with_costs() {
+        let solved = solve_json("two_stage", TWO_STAGE);
+        // «Большой»: затраты 110, 0.7·290 + 0.3·(−60) = 185 > «Малый»: 150 − 50 = 100
+        // «Рынок»: 0.6·185 + 0.4·(−10) = 107 > «Наугад»: 0.5·180 + 0.5·20 = 100
+        let result = &solved.result;
+        assert_eq!(result.chosen, vec!["Исследование"]);
+        assert_eq!(result.scores[0].0, "Исследование");
+        assert!((result.scores[0].1 - 107.0).abs() < 1e-9);
+        assert!((result.scores[1].1 - 100.0).abs() < 1e-9);
+        assert_eq!(result.notes, vec![
+            "Старт: выбрать «Исследование» (значение 107.0000)".to_string(),
+            "Завод: выбрать «Большой» (значение 185.0000)".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn dot_highlights_chosen_branches() {
+        let dot = solve_json("dot", TWO_STAGE).dot;
+        assert!(dot.contains("\"Старт\" -> \"Рынок\" [label=\"Исследование (затраты 10)\", color=red, penwidth=2];"));
+        assert!(dot.contains("\"Старт\" -> \"Наугад\" [label=\"Без исследования\"];"));
+        assert!(dot.contains("\"Завод\" -> \"Спрос\" [label=\"Большой (затраты 100)\", color=red, penwidth=2];"));
+        // ветви узлов случая не выделяются
+        assert!(dot.contains("\"Рынок\" -> \"Завод\" [label=\"Успех (p = 0.6)\"];"));
+        assert_eq!(dot.matches("color=red").count(), 2);
+    }
+
+    #[test]
+    fn chance_node_with_ferstner_criterion() {
+        let tree = |criterion: &str| format!(r#"{{
+            "root": {{ "type": "decision", "branches": [
+                {{ "label": "Риск", "node": {{ "type": "chance", {} "branches": [
+                    {{ "label": "Удача", "probability": 0.5, "node": {{ "type": "terminal", "payoff": 100 }} }},
+                    {{ "label": "Неудача", "probability": 0.5, "node": {{ "type": "terminal", "payoff": -50 }} }}
+                ] }} }},
+                {{ "label": "Надёжно", "node": {{ "type": "terminal", "payoff": 20 }} }}
+            ] }}
+        }}"#, criterion);
+        // Байес: 25 > 20
+        let bayes = solve_json("bayes", &tree("")).result;
+        assert_eq!(bayes.chosen, vec!["Риск"]);
+        assert!((bayes.scores[0].1 - 25.0).abs() < 1e-9);
+        // Ферстнер, λ = 0.5: 0.5·(−50) + 0.5·25 = −12.5 < 20
+        let ferstner = solve_json("ferstner", &tree(r#""criterion": "ferstner", "lambda": 0.5,"#)).result;
+        assert_eq!(ferstner.chosen, vec!["Надёжно"]);
+        let risky = ferstner.scores.iter().find(|(id, _)| id == "Риск").unwrap().1;
+        assert!((risky + 12.5).abs() < 1e-9);
+    }
+}
@@ -7,6 +7,7 @@
 //! оптимальное решение по первому критерию задачи, основываясь
 //! на максимизации или минимизации значения.
 use crate::core::base::*;
+use crate::problems::missing::ensure_complete;
 use anyhow::Result;
 
 
@@ -15,23 +16,27 @@ pub struct DeterministicSolver;
 
 impl DeterministicSolver {
     pub fn solve(spec: &ProblemSpec) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
         // Допущение: одномерный критерий (первый в списке). Берем максимум или минимум по флагу.
         if spec.criteria.is_empty() || spec.alternatives.is_empty() {
             anyhow::bail!("Empty problem")
         }
         let crit = &spec.criteria[0];
+        if let Some(alt) = spec.alternatives.iter().find(|a| a.values.is_empty()) {
+            anyhow::bail!("У альтернативы {} нет значения по критерию {}", alt.id, crit.id)
+        }
         let maximize = crit.maximize.unwrap_or(true);
         let mut scores: Vec<(String, f64)> = spec.alternatives.iter()
             .map(|a| (a.id.clone(), a.values[0]))
             .collect();
         if maximize {
-            scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+            scores.sort_by(|a,b| b.1.total_cmp(&a.1));
         } else {
-            scores.sort_by(|a,b| a.1.partial_cmp(&b.1).unwrap());
+            scores.sort_by(|a,b| a.1.total_cmp(&b.1));
         }
         let best_val = scores[0].1;
         let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best_val).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
 
-        Ok(DecisionResult{chosen, scores, method: "deterministic".to_string()})
+        Ok(DecisionResult{chosen, scores, method: "deterministic".to_string(), ..Default::default()})
     }
 }
@@ -0,0 +1,119 @@
+// -----------------------------
+// src/problems/expected_utility.rs
+// -----------------------------
+//! Критерий ожидаемой полезности.
+//!
+//! Критерии из `bayes_rules` и `RiskSolver` сравнивают альтернативы по денежным выплатам.
+//! Здесь выплаты сначала переводятся в полезности функцией `ProblemSpec.utility`
+//! (линейная, экспоненциальная CARA, логарифмическая, степенная CRRA, кусочно-линейная
+//! по выявленным точкам), затем для каждой альтернативы считаются:
+//! - ожидаемая полезность EU = Σ p_j·u(a_ij);
+//! - детерминированный эквивалент CE: u(CE) = EU — гарантированная сумма, равноценная лотерее;
+//! - премия за риск RP = M − CE: сколько лицо готово отдать за устранение неопределённости.
+//!
+//! При неприятии риска (вогнутая u) RP > 0, и альтернатива с меньшим ожиданием,
+//! но меньшим разбросом может оказаться предпочтительнее.
+use crate::core::base::*;
+use crate::problems::maut::{certainty_equivalent, utility};
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+
+pub struct ExpectedUtilitySolver;
+
+
+impl ExpectedUtilitySolver {
+    pub fn solve(spec: &ProblemSpec) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for expected utility"))?;
+        if spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+        if spec.alternatives.iter().any(|a| a.values.len() != probs.len()) {
+            anyhow::bail!("Alternative values length must equal number of states");
+        }
+
+        // полезность нормируется на диапазон всех выплат задачи
+        let all = spec.alternatives.iter().flat_map(|a| a.values.iter().cloned());
+        let (lo, hi) = all.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let default = UtilityFunction::Linear { min: None, max: None };
+        let func = spec.utility.as_ref().unwrap_or(&default);
+
+        let mut rows = Vec::new();
+        for alt in &spec.alternatives {
+            let expected: f64 = alt.values.iter().zip(probs.iter()).map(|(v,p)| v * p).sum();
+            let eu = alt.values.iter().zip(probs.iter())
+                .map(|(v,p)| Ok(p * utility(func, *v, lo, hi, true)?))
+                .sum::<Result<f64>>()?;
+            let ce = certainty_equivalent(func, eu, lo, hi)?;
+            rows.push((alt.id.clone(), vec![expected, eu, ce, expected - ce]));
+        }
+
+        let mut scores: Vec<(String, f64)> = rows.iter().map(|(id, r)| (id.clone(), r[1])).collect();
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+
+        let mut notes = Vec::new();
+        let by_ev = rows.iter().fold(&rows[0], |b, r| if r.1[0] > b.1[0] + 1e-9 { r } else { b });
+        if !chosen.contains(&by_ev.0) {
+            let picked = rows.iter().find(|(id, _)| *id == chosen[0]).unwrap();
+            notes.push(format!(
+                "По ожидаемому значению лучше {} (M = {:.4}), но с учётом отношения к риску выбрана {}: \
+                 её детерминированный эквивалент {:.4} больше, чем {:.4} (премии за риск {:.4} и {:.4})",
+                by_ev.0, by_ev.1[0], picked.0, picked.1[2], by_ev.1[2], picked.1[3], by_ev.1[3]
+            ));
+        }
+
+        let table = ResultTable {
+            title: "Ожидаемая полезность".to_string(),
+            columns: ["M", "EU", "CE", "премия за риск"].iter().map(|c| c.to_string()).collect(),
+            rows,
+        };
+        Ok(DecisionResult { chosen, scores, method: "expected_utility".to_string(), tables: vec![table], notes, ..Default::default() })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::read_spec;
+
+    #[test]
+    fn cara_prefers_smaller_order() {
+        let spec = read_spec("data/data_for_risk_utility.json").unwrap();
+        let p = spec.state_probabilities.clone().unwrap();
+        let result = ExpectedUtilitySolver::solve(&spec).unwrap();
+        assert_eq!(result.chosen, vec!["При 150"]);
+        assert_eq!(result.notes.len(), 1);
+
+        // CARA, a = 0.004: CE = −ln Σ p_j·e^{−a·x_j} / a; EU нормирована на диапазон выплат [−300, 2100]
+        let a = 0.004;
+        let u = |x: f64| (1.0 - (-a * x).exp()) / a;
+        let row = |id: &str| &result.tables[0].rows.iter().find(|(r, _)| r == id).unwrap().1;
+        for (id, x, mean) in [
+            ("При 150", [450.0, 1050.0, 1050.0, 1050.0, 1050.0], 990.0),
+            ("При 200", [200.0, 800.0, 1400.0, 1400.0, 1400.0], 1100.0),
+        ] {
+            let eu = x.iter().zip(p.iter()).map(|(v, q)| q * (u(*v) - u(-300.0)) / (u(2100.0) - u(-300.0))).sum::<f64>();
+            let ce = -x.iter().zip(p.iter()).map(|(v, q)| q * (-a * v).exp()).sum::<f64>().ln() / a;
+            let r = row(id);
+            assert!((r[0] - mean).abs() < 1e-9);
+            assert!((r[1] - eu).abs() < 1e-9);
+            assert!((r[2] - ce).abs() < 1e-6);
+            assert!((r[3] - (mean - ce)).abs() < 1e-6);
+        }
+        // CE ≈ 876.42 против 705.95: премия за риск у «При 200» больше ожидаемого выигрыша
+        assert!((row("При 150")[2] - 876.4237).abs() < 1e-3);
+        assert!((row("При 200")[2] - 705.9483).abs() < 1e-3);
+    }
+
+    #[test]
+    fn linear_utility_follows_expected_value() {
+        let mut spec = read_spec("data/data_for_risk_utility.json").unwrap();
+        spec.utility = None;
+        let result = ExpectedUtilitySolver::solve(&spec).unwrap();
+        assert_eq!(result.chosen, vec!["При 200"]);
+        assert!(result.tables[0].rows.iter().all(|(_, r)| r[3].abs() < 1e-6));
+        assert!(result.notes.is_empty());
+    }
+}
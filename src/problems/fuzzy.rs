@@ -0,0 +1,290 @@
+// -----------------------------
+// src/problems/fuzzy.rs
+// -----------------------------
+//! Взвешенная сумма и TOPSIS для интервальных и нечётких матриц решений.
+//!
+//! Значения альтернатив могут быть числами, интервалами [a, b] или треугольными
+//! нечёткими числами [l, m, u] (`Alternative.cells`). Решатель работает в одном из режимов:
+//! - интервальный: треугольное число заменяется своим носителем [l, u];
+//! - нечёткий: интервал [a, b] заменяется треугольным числом (a, (a+b)/2, b).
+//!
+//! Правила ранжирования:
+//! - взвешенная сумма (интервалы) — степень возможности P(A ≥ B) и вектор ранжирования по матрице возможностей;
+//! - взвешенная сумма (нечёткие) — степень возможности V(A ≥ B) по Чангу, оценка = min_B V(A ≥ B);
+//! - TOPSIS — коэффициент близости к идеальному решению (вершинное расстояние).
+use crate::core::base::*;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UncertaintyMode {
+    Interval,
+    Fuzzy,
+}
+
+
+/// Треугольное нечёткое число (l, m, u); интервал хранится как (a, (a+b)/2, b)
+#[derive(Debug, Clone, Copy)]
+struct Tfn {
+    l: f64,
+    m: f64,
+    u: f64,
+}
+
+
+impl Tfn {
+    fn from_cell(cell: &CellValue) -> Tfn {
+        match *cell {
+            CellValue::Crisp(x) => Tfn { l: x, m: x, u: x },
+            CellValue::Interval([a, b]) => Tfn { l: a, m: (a + b) / 2.0, u: b },
+            CellValue::Triangular([l, m, u]) => Tfn { l, m, u },
+        }
+    }
+
+    fn scale(&self, k: f64) -> Tfn {
+        Tfn { l: self.l * k, m: self.m * k, u: self.u * k }
+    }
+
+    fn add(&self, other: &Tfn) -> Tfn {
+        Tfn { l: self.l + other.l, m: self.m + other.m, u: self.u + other.u }
+    }
+
+    /// Вершинное расстояние; в интервальном режиме учитываются только границы
+    fn distance(&self, other: &Tfn, mode: UncertaintyMode) -> f64 {
+        let (dl, dm, du) = (self.l - other.l, self.m - other.m, self.u - other.u);
+        match mode {
+            UncertaintyMode::Interval => ((dl * dl + du * du) / 2.0).sqrt(),
+            UncertaintyMode::Fuzzy => ((dl * dl + dm * dm + du * du) / 3.0).sqrt(),
+        }
+    }
+}
+
+
+pub struct FuzzySolver;
+
+
+impl FuzzySolver {
+    /// method: "weighted-sum" | "topsis"
+    pub fn solve(spec: &ProblemSpec, method: &str, mode: UncertaintyMode) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let n = spec.criteria.len();
+        if n == 0 || spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+        if spec.alternatives.iter().any(|a| a.cells.len() != n) { anyhow::bail!("Alternative values length mismatch") }
+
+        let weights: Vec<f64> = spec.criteria.iter().map(|c| c.weight.unwrap_or(1.0)).collect();
+        let sw: f64 = weights.iter().sum();
+        let weights: Vec<f64> = weights.into_iter().map(|w| w / sw).collect();
+
+        // Матрица: в интервальном режиме середина не используется, поэтому треугольное число сводится к носителю
+        let matrix: Vec<Vec<Tfn>> = spec.alternatives.iter()
+            .map(|a| a.cells.iter().map(|c| {
+                let t = Tfn::from_cell(c);
+                if mode == UncertaintyMode::Interval { Tfn { m: (t.l + t.u) / 2.0, ..t } } else { t }
+            }).collect())
+            .collect();
+        if matrix.iter().flatten().any(|t| t.l > t.m || t.m > t.u) {
+            anyhow::bail!("Нечёткие числа и интервалы должны быть упорядочены: l ≤ m ≤ u");
+        }
+
+        let normalized = normalize(&matrix, spec)?;
+        let weighted: Vec<Vec<Tfn>> = normalized.iter()
+            .map(|row| row.iter().zip(weights.iter()).map(|(t, w)| t.scale(*w)).collect())
+            .collect();
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+
+        let mode_name = match mode { UncertaintyMode::Interval => "interval", UncertaintyMode::Fuzzy => "fuzzy" };
+        let (mut scores, tables) = match method {
+            "weighted-sum" => {
+                let totals: Vec<Tfn> = weighted.iter()
+                    .map(|row| row.iter().fold(Tfn { l: 0.0, m: 0.0, u: 0.0 }, |acc, t| acc.add(t)))
+                    .collect();
+                let k = totals.len();
+                let possibility: Vec<Vec<f64>> = totals.iter()
+                    .map(|a| totals.iter().map(|b| match mode {
+                        UncertaintyMode::Interval => interval_possibility(a, b),
+                        UncertaintyMode::Fuzzy => tfn_possibility(a, b),
+                    }).collect())
+                    .collect();
+                let scores: Vec<f64> = match mode {
+                    // вектор ранжирования по матрице возможностей (Сюй, Да)
+                    UncertaintyMode::Interval if k > 1 => possibility.iter()
+                        .map(|row| (row.iter().sum::<f64>() + k as f64 / 2.0 - 1.0) / (k * (k - 1)) as f64)
+                        .collect(),
+                    UncertaintyMode::Interval => vec![1.0],
+                    UncertaintyMode::Fuzzy => possibility.iter()
+                        .enumerate()
+                        .map(|(i, row)| row.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, v)| *v).fold(1.0, f64::min))
+                        .collect(),
+                };
+                let (columns, rows) = match mode {
+                    UncertaintyMode::Interval => (
+                        vec!["нижняя".to_string(), "верхняя".to_string()],
+                        ids.iter().cloned().zip(totals.iter().map(|t| vec![t.l, t.u])).collect(),
+                    ),
+                    UncertaintyMode::Fuzzy => (
+                        vec!["l".to_string(), "m".to_string(), "u".to_string()],
+                        ids.iter().cloned().zip(totals.iter().map(|t| vec![t.l, t.m, t.u])).collect(),
+                    ),
+                };
+                let tables = vec![
+                    ResultTable { title: "Взвешенные суммы".to_string(), columns, rows },
+                    ResultTable {
+                        title: "Степени возможности (строка ≥ столбец)".to_string(),
+                        columns: ids.clone(),
+                        rows: ids.iter().cloned().zip(possibility).collect(),
+                    },
+                ];
+                (ids.iter().cloned().zip(scores).collect::<Vec<(String, f64)>>(), tables)
+            },
+            "topsis" => {
+                // после нормализации все критерии максимизируемые: идеал — покомпонентный максимум по столбцу, антиидеал — минимум
+                let ideal: Vec<Tfn> = (0..n).map(|j| weighted.iter().fold(weighted[0][j], |acc, row| {
+                    Tfn { l: acc.l.max(row[j].l), m: acc.m.max(row[j].m), u: acc.u.max(row[j].u) }
+                })).collect();
+                let anti: Vec<Tfn> = (0..n).map(|j| weighted.iter().fold(weighted[0][j], |acc, row| {
+                    Tfn { l: acc.l.min(row[j].l), m: acc.m.min(row[j].m), u: acc.u.min(row[j].u) }
+                })).collect();
+                let dist = |row: &[Tfn], target: &[Tfn]| -> f64 {
+                    row.iter().zip(target.iter()).map(|(a, b)| a.distance(b, mode)).sum()
+                };
+                let rows: Vec<(String, Vec<f64>)> = weighted.iter().zip(ids.iter())
+                    .map(|(row, id)| {
+                        let (d_plus, d_minus) = (dist(row, &ideal), dist(row, &anti));
+                        let cc = if d_plus + d_minus > 0.0 { d_minus / (d_plus + d_minus) } else { 1.0 };
+                        (id.clone(), vec![d_plus, d_minus, cc])
+                    })
+                    .collect();
+                let scores = rows.iter().map(|(id, v)| (id.clone(), v[2])).collect();
+                let table = ResultTable {
+                    title: "Расстояния до идеального и антиидеального решений".to_string(),
+                    columns: vec!["d+".to_string(), "d-".to_string(), "близость".to_string()],
+                    rows,
+                };
+                (scores, vec![table])
+            },
+            _ => anyhow::bail!("Unknown method: {}", method)
+        };
+
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+        let weights = spec.criteria.iter().map(|c| c.id.clone()).zip(weights).collect();
+
+        Ok(DecisionResult {
+            chosen,
+            scores,
+            method: format!("{}_{}", mode_name, method),
+            weights: Some(weights),
+            tables,
+            ..Default::default()
+        })
+    }
+}
+
+
+/// Линейная нормализация: максимизируемые — x / max u, минимизируемые — min l / x (с обращением порядка границ)
+fn normalize(matrix: &[Vec<Tfn>], spec: &ProblemSpec) -> Result<Vec<Vec<Tfn>>> {
+    let mut out = matrix.to_vec();
+    for (j, crit) in spec.criteria.iter().enumerate() {
+        if crit.maximize.unwrap_or(true) {
+            let max_u = matrix.iter().map(|row| row[j].u).fold(f64::NEG_INFINITY, f64::max);
+            if max_u <= 0.0 { anyhow::bail!("Критерий {}: для нормализации значения должны быть положительными", crit.id) }
+            for row in out.iter_mut() {
+                row[j] = row[j].scale(1.0 / max_u);
+            }
+        } else {
+            let min_l = matrix.iter().map(|row| row[j].l).fold(f64::INFINITY, f64::min);
+            if min_l <= 0.0 { anyhow::bail!("Критерий {}: для нормализации значения должны быть положительными", crit.id) }
+            for row in out.iter_mut() {
+                let t = row[j];
+                row[j] = Tfn { l: min_l / t.u, m: min_l / t.m, u: min_l / t.l };
+            }
+        }
+    }
+    Ok(out)
+}
+
+
+/// Степень возможности для интервалов: P(A ≥ B) = min(1, max(0, (a_u − b_l) / (len A + len B)))
+fn interval_possibility(a: &Tfn, b: &Tfn) -> f64 {
+    let len = (a.u - a.l) + (b.u - b.l);
+    if len == 0.0 {
+        return if a.l > b.l { 1.0 } else if a.l < b.l { 0.0 } else { 0.5 };
+    }
+    ((a.u - b.l) / len).clamp(0.0, 1.0)
+}
+
+
+/// Степень возможности Чанга: V(A ≥ B) = 1 при m_A ≥ m_B, 0 при l_B ≥ u_A, иначе (l_B − u_A) / ((m_A − u_A) − (m_B − l_B))
+fn tfn_possibility(a: &Tfn, b: &Tfn) -> f64 {
+    if a.m >= b.m {
+        1.0
+    } else if b.l >= a.u {
+        0.0
+    } else {
+        (b.l - a.u) / ((a.m - a.u) - (b.m - b.l))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(result: &DecisionResult, id: &str) -> f64 {
+        result.scores.iter().find(|(a, _)| a == id).unwrap().1
+    }
+
+    #[test]
+    fn interval_weighted_sum_possibility_ranking() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "c", "maximize": true }],
+            "alternatives": [
+                { "id": "A", "values": [[2, 4]] },
+                { "id": "B", "values": [[1, 3]] }
+            ]
+        }"#).unwrap();
+        // A = [0.5, 1], B = [0.25, 0.75]: P(A ≥ B) = 0.75, P(A ≥ A) = 0.5
+        let result = FuzzySolver::solve(&spec, "weighted-sum", UncertaintyMode::Interval).unwrap();
+        assert!((result.tables[1].rows[0].1[1] - 0.75).abs() < 1e-12);
+        assert!((score(&result, "A") - 0.625).abs() < 1e-12);
+        assert!((score(&result, "B") - 0.375).abs() < 1e-12);
+        assert_eq!(result.chosen, vec!["A"]);
+    }
+
+    #[test]
+    fn fuzzy_weighted_sum_uses_chang_possibility() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "c", "maximize": true }],
+            "alternatives": [
+                { "id": "A", "values": [[1, 2, 3]] },
+                { "id": "B", "values": [[2, 3, 4]] }
+            ]
+        }"#).unwrap();
+        // A = (0.25, 0.5, 0.75), B = (0.5, 0.75, 1): V(A ≥ B) = −0.25 / −0.5
+        let result = FuzzySolver::solve(&spec, "weighted-sum", UncertaintyMode::Fuzzy).unwrap();
+        assert!((score(&result, "A") - 0.5).abs() < 1e-12);
+        assert!((score(&result, "B") - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn interval_topsis_closeness() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "c1", "maximize": true }, { "id": "c2", "maximize": false }],
+            "alternatives": [
+                { "id": "A", "values": [[2, 4], [2, 4]] },
+                { "id": "B", "values": [[1, 3], [1, 2]] }
+            ]
+        }"#).unwrap();
+        // взвешенные: A = ([0.25, 0.5], [0.125, 0.25]), B = ([0.125, 0.375], [0.25, 0.5])
+        let far = ((0.125f64.powi(2) + 0.25f64.powi(2)) / 2.0).sqrt();
+        let result = FuzzySolver::solve(&spec, "topsis", UncertaintyMode::Interval).unwrap();
+        let a = &result.tables[0].rows[0].1;
+        assert!((a[0] - far).abs() < 1e-12);
+        assert!((a[1] - 0.125).abs() < 1e-12);
+        assert!((score(&result, "A") - 0.125 / (0.125 + far)).abs() < 1e-12);
+        assert!((score(&result, "B") - far / (0.125 + far)).abs() < 1e-12);
+        assert_eq!(result.chosen, vec!["B"]);
+    }
+}
@@ -0,0 +1,474 @@
+// -----------------------------
+// src/problems/history_estimation.rs
+// -----------------------------
+//! Оценка вероятностей состояний по истории наблюдений (например, фактического спроса по месяцам).
+//!
+//! Состояния — числовые уровни x_1 < … < x_k (из файла истории, `state_values` или чисел в названиях
+//! состояний). Наблюдение относится к ближайшему уровню: границы интервалов — середины между соседними
+//! уровнями, крайние интервалы открыты. Способы оценки:
+//! - frequency — относительные частоты n_j / N;
+//! - laplace — сглаживание Лапласа (n_j + α) / (N + k·α), по умолчанию α = 1: короткая история
+//!   не даёт состоянию нулевую вероятность;
+//! - poisson, normal, negative-binomial — распределение подбирается по среднему и дисперсии
+//!   наблюдений (метод моментов) и переносится на интервалы состояний.
+//!
+//! Для подобранных распределений считается согласие с данными: χ² Пирсона по интервалам состояний
+//! с p-значением, логарифм правдоподобия и AIC.
+//!
+//! История читается из CSV (значение — последнее поле строки, нечисловая первая строка — заголовок)
+//! или JSON: массив чисел либо объект {"states": [...], "observations": [...]}.
+use crate::core::base::{ProblemSpec, ResultTable};
+use anyhow::Result;
+use serde::Deserialize;
+
+const EPS: f64 = 1e-12;
+// ln p(x), ниже которого слагаемые дискретного распределения отбрасываются (p < 10⁻¹⁸)
+const LOG_TAIL: f64 = -41.4;
+
+
+/// История наблюдений и, если заданы, уровни состояний
+#[derive(Debug, Deserialize)]
+pub struct History {
+    pub states: Option<Vec<f64>>,
+    pub observations: Vec<f64>,
+}
+
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawHistory {
+    Full(History),
+    Plain(Vec<f64>),
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EstimationMethod {
+    Frequency,
+    Laplace,
+    Poisson,
+    Normal,
+    NegativeBinomial,
+}
+
+
+impl EstimationMethod {
+    /// "frequency" | "laplace" | "poisson" | "normal" | "negative-binomial"
+    pub fn from_name(name: &str) -> Result<Self> {
+        Ok(match name {
+            "frequency" => EstimationMethod::Frequency,
+            "laplace" => EstimationMethod::Laplace,
+            "poisson" => EstimationMethod::Poisson,
+            "normal" => EstimationMethod::Normal,
+            "negative-binomial" => EstimationMethod::NegativeBinomial,
+            _ => anyhow::bail!("Unknown estimation method: {}", name),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EstimationMethod::Frequency => "frequency",
+            EstimationMethod::Laplace => "laplace",
+            EstimationMethod::Poisson => "poisson",
+            EstimationMethod::Normal => "normal",
+            EstimationMethod::NegativeBinomial => "negative-binomial",
+        }
+    }
+
+    /// Число оцениваемых по данным параметров распределения (для χ² и AIC)
+    fn parameters(&self) -> usize {
+        match self {
+            EstimationMethod::Poisson => 1,
+            EstimationMethod::Normal | EstimationMethod::NegativeBinomial => 2,
+            EstimationMethod::Frequency | EstimationMethod::Laplace => 0,
+        }
+    }
+}
+
+
+/// Оценка выбранным способом и сравнение всех способов
+pub struct HistoryEstimate {
+    pub probabilities: Vec<f64>,
+    pub tables: Vec<ResultTable>,
+    pub notes: Vec<String>,
+}
+
+
+/// Чтение истории из CSV (по расширению .csv) или JSON
+pub fn read_history(path: &str) -> Result<History> {
+    let text = std::fs::read_to_string(path)?;
+    let history = if path.to_lowercase().ends_with(".csv") {
+        History { states: None, observations: parse_csv(&text)? }
+    } else {
+        match serde_json::from_str(&text)? {
+            RawHistory::Full(history) => history,
+            RawHistory::Plain(observations) => History { states: None, observations },
+        }
+    };
+    if history.observations.is_empty() {
+        anyhow::bail!("История наблюдений пуста: {}", path);
+    }
+    if let Some(v) = history.observations.iter().find(|v| !v.is_finite()) {
+        anyhow::bail!("Наблюдение должно быть конечным числом: {}", v);
+    }
+    Ok(history)
+}
+
+
+fn parse_csv(text: &str) -> Result<Vec<f64>> {
+    let mut observations = Vec::new();
+    let mut header = false;
+    for (k, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let field = line.rsplit([',', ';', '\t']).next().unwrap_or(line).trim();
+        match field.parse::<f64>() {
+            Ok(v) => observations.push(v),
+            Err(_) if observations.is_empty() && !header => header = true,
+            Err(_) => anyhow::bail!("Строка {} истории: ожидалось число, получено \"{}\"", k + 1, field),
+        }
+    }
+    Ok(observations)
+}
+
+
+/// Уровни состояний с названиями: из истории или из задачи (`ProblemSpec::state_values`);
+/// названия берутся из задачи, без неё — сами уровни
+pub fn history_states(history: &History, spec: Option<&ProblemSpec>) -> Result<Vec<(String, f64)>> {
+    let count = spec.and_then(|s| s.alternatives.first()).map(|a| a.values.len());
+    let values = match (&history.states, spec) {
+        (Some(values), _) => values.clone(),
+        (None, Some(spec)) => spec.state_values(count.unwrap_or(spec.criteria.len()))
+            .ok_or_else(|| anyhow::anyhow!("Уровни состояний не заданы: укажите states в истории, state_values или числа в названиях состояний"))?,
+        (None, None) => anyhow::bail!("Уровни состояний не заданы: укажите states в истории или задачу (--spec)"),
+    };
+    if values.is_empty() || values.windows(2).any(|w| w[0] >= w[1]) {
+        anyhow::bail!("Уровни состояний должны строго возрастать: {:?}", values);
+    }
+    let names = match (spec, count) {
+        (Some(spec), Some(n)) => {
+            if n != values.len() {
+                anyhow::bail!("Уровней состояний {}, а столбцов в задаче {}", values.len(), n);
+            }
+            spec.state_names(n)
+        }
+        _ => values.iter().map(|v| format!("{}", v)).collect(),
+    };
+    Ok(names.into_iter().zip(values).collect())
+}
+
+
+/// Вероятности состояний по истории выбранным способом; alpha — параметр сглаживания Лапласа
+pub fn estimate_probabilities(observations: &[f64], states: &[(String, f64)], method: EstimationMethod, alpha: Option<f64>) -> Result<HistoryEstimate> {
+    let k = states.len();
+    let n = observations.len() as f64;
+    // границы интервалов: −∞, середины между соседними уровнями, +∞
+    let mut edges = vec![f64::NEG_INFINITY];
+    edges.extend(states.windows(2).map(|w| (w[0].1 + w[1].1) / 2.0));
+    edges.push(f64::INFINITY);
+    let mut counts = vec![0.0; k];
+    for v in observations {
+        counts[edges[1..k].iter().filter(|b| **b <= *v).count()] += 1.0;
+    }
+    let mean = observations.iter().sum::<f64>() / n;
+    let variance = if observations.len() > 1 {
+        observations.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        f64::NAN
+    };
+    let alpha = alpha.unwrap_or(1.0);
+    if alpha <= 0.0 {
+        anyhow::bail!("Параметр сглаживания Лапласа должен быть положительным: {}", alpha);
+    }
+
+    let fit = |method: EstimationMethod| -> Result<(String, Vec<f64>)> {
+        Ok(match method {
+            EstimationMethod::Frequency => ("частоты".to_string(), counts.iter().map(|c| c / n).collect()),
+            EstimationMethod::Laplace => (
+                format!("Лаплас (α = {})", alpha),
+                counts.iter().map(|c| (c + alpha) / (n + k as f64 * alpha)).collect(),
+            ),
+            EstimationMethod::Poisson => {
+                if observations.iter().any(|v| *v < 0.0) || mean <= 0.0 {
+                    anyhow::bail!("Распределение Пуассона требует неотрицательных наблюдений с положительным средним");
+                }
+                // мода ⌊λ⌋, ln p(x) = x·ln λ − λ − ln x!, ln p(x+1) − ln p(x) = ln λ − ln(x + 1)
+                let mode = mean.floor();
+                let log_p_mode = mode * mean.ln() - mean - ln_gamma(mode + 1.0);
+                let probs = discrete_bins(&edges, mode, log_p_mode, |x| mean.ln() - (x + 1.0).ln());
+                (format!("Пуассон (λ = {:.2})", mean), probs)
+            }
+            EstimationMethod::Normal => {
+                if variance.is_nan() || variance <= 0.0 {
+                    anyhow::bail!("Нормальное распределение требует хотя бы двух различных наблюдений");
+                }
+                let sigma = variance.sqrt();
+                let probs = (0..k).map(|j| normal_cdf((edges[j + 1] - mean) / sigma) - normal_cdf((edges[j] - mean) / sigma)).collect();
+                (format!("нормальное (μ = {:.2}, σ = {:.2})", mean, sigma), probs)
+            }
+            EstimationMethod::NegativeBinomial => {
+                if observations.iter().any(|v| *v < 0.0) || variance.is_nan() || variance <= mean {
+                    anyhow::bail!("Отрицательное биномиальное распределение требует неотрицательных наблюдений с дисперсией больше среднего");
+                }
+                // метод моментов: p = M / D, r = M² / (D − M)
+                let p = mean / variance;
+                let r = mean * mean / (variance - mean);
+                // мода ⌊(r − 1)(1 − p) / p⌋ при r > 1, иначе 0;
+                // ln p(x) = ln Γ(x + r) − ln Γ(r) − ln x! + r·ln p + x·ln(1 − p),
+                // ln p(x+1) − ln p(x) = ln(x + r) − ln(x + 1) + ln(1 − p)
+                let mode = if r > 1.0 { ((r - 1.0) * (1.0 - p) / p).floor() } else { 0.0 };
+                let log_p_mode = ln_gamma(mode + r) - ln_gamma(r) - ln_gamma(mode + 1.0) + r * p.ln() + mode * (1.0 - p).ln();
+                let probs = discrete_bins(&edges, mode, log_p_mode, |x| (x + r).ln() - (x + 1.0).ln() + (1.0 - p).ln());
+                (format!("отр. биномиальное (r = {:.2}, p = {:.4})", r, p), probs)
+            }
+        })
+    };
+
+    let (_, probabilities) = fit(method)?;
+    let names: Vec<String> = states.iter().map(|(s, _)| s.clone()).collect();
+    let mut notes = vec![format!("Наблюдений: {}, среднее {:.2}, дисперсия {:.2}", observations.len(), mean, variance)];
+    let mut by_model = Vec::new();
+    let mut goodness = Vec::new();
+    let mut small_expected = false;
+    for candidate in [
+        EstimationMethod::Frequency,
+        EstimationMethod::Laplace,
+        EstimationMethod::Poisson,
+        EstimationMethod::Normal,
+        EstimationMethod::NegativeBinomial,
+    ] {
+        let (label, probs) = match fit(candidate) {
+            Ok(fitted) => fitted,
+            Err(e) => {
+                notes.push(format!("{}: {}", candidate.name(), e));
+                continue;
+            }
+        };
+        let log_likelihood: f64 = counts.iter().zip(probs.iter()).filter(|(c, _)| **c > 0.0).map(|(c, p)| c * p.ln()).sum();
+        if candidate == EstimationMethod::Frequency {
+            notes.push(format!("ln L по частотам (наилучшее достижимое) = {:.4}", log_likelihood));
+        }
+        if candidate.parameters() > 0 {
+            let chi2: f64 = counts.iter().zip(probs.iter())
+                .map(|(c, p)| {
+                    let expected = n * p;
+                    if expected > EPS { (c - expected).powi(2) / expected } else if *c > 0.0 { f64::INFINITY } else { 0.0 }
+                })
+                .sum();
+            small_expected |= probs.iter().any(|p| n * p < 5.0);
+            let dof = k as f64 - 1.0 - candidate.parameters() as f64;
+            let p_value = if dof > 0.0 { gamma_q(dof / 2.0, chi2 / 2.0) } else { f64::NAN };
+            let aic = 2.0 * candidate.parameters() as f64 - 2.0 * log_likelihood;
+            goodness.push((label.clone(), vec![chi2, dof, p_value, log_likelihood, aic]));
+        }
+        by_model.push((label, probs));
+    }
+    if small_expected {
+        notes.push("χ² приближён: есть состояния с ожидаемым числом наблюдений меньше 5".to_string());
+    }
+    if goodness.iter().any(|(_, row)| row[1] <= 0.0) {
+        notes.push("Состояний слишком мало для проверки χ² при таком числе параметров (p-значение не определено)".to_string());
+    }
+    if let Some((label, _)) = goodness.iter().min_by(|a, b| a.1[4].total_cmp(&b.1[4])) {
+        notes.push(format!("Лучшее по AIC распределение: {}", label));
+    }
+
+    let tables = vec![
+        ResultTable {
+            title: "Наблюдения по состояниям".to_string(),
+            columns: vec!["от".to_string(), "до".to_string(), "наблюдений".to_string(), "частота".to_string()],
+            rows: (0..k).map(|j| (names[j].clone(), vec![edges[j], edges[j + 1], counts[j], counts[j] / n])).collect(),
+        },
+        ResultTable { title: "Вероятности состояний по моделям".to_string(), columns: names, rows: by_model },
+        ResultTable {
+            title: "Согласие с данными".to_string(),
+            columns: vec!["χ²".to_string(), "ст. свободы".to_string(), "p-значение".to_string(), "ln L".to_string(), "AIC".to_string()],
+            rows: goodness,
+        },
+    ];
+    Ok(HistoryEstimate { probabilities, tables, notes })
+}
+
+
+/// Вероятности интервалов [edges_j, edges_{j+1}) для унимодального распределения на 0, 1, 2, …,
+/// заданного модой, ln p(моды) и приращением ln p(x+1) − ln p(x). Суммирование идёт от моды в обе стороны
+/// и останавливается, когда p(x) становится пренебрежимо малой, поэтому число шагов — порядка
+/// нескольких стандартных отклонений, а не величины наибольшей границы
+fn discrete_bins(edges: &[f64], mode: f64, log_p_mode: f64, log_step: impl Fn(f64) -> f64) -> Vec<f64> {
+    let finite = &edges[1..edges.len() - 1];
+    let mut bins = vec![0.0; edges.len() - 1];
+    let mut add = |x: f64, log_p: f64| bins[finite.partition_point(|b| *b <= x)] += log_p.exp();
+    add(mode, log_p_mode);
+    // вниз: ln p(x − 1) = ln p(x) − (ln p(x) − ln p(x − 1))
+    let (mut x, mut log_p) = (mode, log_p_mode);
+    while x > 0.0 {
+        log_p -= log_step(x - 1.0);
+        x -= 1.0;
+        if log_p < LOG_TAIL {
+            break;
+        }
+        add(x, log_p);
+    }
+    // вверх
+    let (mut x, mut log_p) = (mode, log_p_mode);
+    loop {
+        log_p += log_step(x);
+        x += 1.0;
+        if log_p < LOG_TAIL {
+            break;
+        }
+        add(x, log_p);
+    }
+    // отброшенные хвосты пренебрежимо малы; нормировка заодно гасит погрешность ln Γ в ln p(моды)
+    let total: f64 = bins.iter().sum();
+    bins.into_iter().map(|b| b / total).collect()
+}
+
+
+/// Функция стандартного нормального распределения Φ(z)
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+
+/// Дополнительная функция ошибок (чебышёвская аппроксимация, относительная погрешность < 1.2·10⁻⁷)
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.5 * x.abs());
+    let poly = -1.26551223 + t * (1.00002368 + t * (0.37409196 + t * (0.09678418 + t * (-0.18628806
+        + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let value = t * (-x * x + poly).exp();
+    if x >= 0.0 { value } else { 2.0 - value }
+}
+
+
+/// ln Γ(x) для x > 0 (приближение Ланцоша)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8, 771.323_428_777_653_1,
+        -176.615_029_162_140_6, 12.507_343_278_686_905, -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..].iter().enumerate().fold(COEFFICIENTS[0], |s, (i, c)| s + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+
+/// Регуляризованная верхняя неполная гамма-функция Q(a, x); p-значение χ² с d степенями свободы — Q(d/2, χ²/2)
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    if x.is_infinite() {
+        return 0.0;
+    }
+    let log_prefix = -x + a * x.ln() - ln_gamma(a);
+    if x < a + 1.0 {
+        // ряд для P(a, x)
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..1000 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * log_prefix.exp()
+    } else {
+        // цепная дробь для Q(a, x) (метод Ленца)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny { d = tiny; }
+            c = b + an / c;
+            if c.abs() < tiny { c = tiny; }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        log_prefix.exp() * h
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(values: &[f64]) -> Vec<(String, f64)> {
+        values.iter().map(|v| (format!("Спрос {}", v), *v)).collect()
+    }
+
+    #[test]
+    fn special_functions() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975_002_1).abs() < 1e-6);
+        assert!((normal_cdf(-1.96) - 0.024_997_9).abs() < 1e-6);
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+        // Q(1, x) = e^{−x}: ряд при x < 2 и цепная дробь при x ≥ 2
+        for x in [0.3, 1.5, 2.5, 10.0] {
+            assert!((gamma_q(1.0, x) - (-x).exp()).abs() < 1e-12);
+        }
+        // χ² с двумя степенями свободы: p = e^{−χ²/2}
+        assert!((gamma_q(1.0, 5.991 / 2.0) - 0.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn poisson_bins() {
+        // среднее 1 ⇒ λ = 1: P(0) = P(1) = e^{−1}, P(X ≥ 2) = 1 − 2/e
+        let estimate = estimate_probabilities(&[0.0, 1.0, 2.0], &states(&[0.0, 1.0, 2.0]), EstimationMethod::Poisson, None).unwrap();
+        let e = (-1.0f64).exp();
+        for (p, expected) in estimate.probabilities.iter().zip([e, e, 1.0 - 2.0 * e]) {
+            assert!((p - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn laplace_smoothing() {
+        // частоты (2, 1, 0) из 3 ⇒ (2 + 1, 1 + 1, 0 + 1) / (3 + 3)
+        let observations = [100.0, 110.0, 150.0];
+        let levels = states(&[100.0, 150.0, 200.0]);
+        let estimate = estimate_probabilities(&observations, &levels, EstimationMethod::Laplace, None).unwrap();
+        for (p, expected) in estimate.probabilities.iter().zip([0.5, 1.0 / 3.0, 1.0 / 6.0]) {
+            assert!((p - expected).abs() < 1e-12);
+        }
+        let frequency = estimate_probabilities(&observations, &levels, EstimationMethod::Frequency, None).unwrap();
+        assert_eq!(frequency.probabilities[2], 0.0);
+        assert!(estimate_probabilities(&observations, &levels, EstimationMethod::Laplace, Some(0.0)).is_err());
+    }
+    #[test]
+    fn negative_binomial_bins() {
+        // M = 2, D = 4 ⇒ p = 1/2, r = 2: p(x) = (x + 1) / 2^{x+2}, мода 1
+        let observations = [0.0, 0.0, 2.0, 4.0, 4.0];
+        let levels = states(&[0.0, 1.0, 2.0, 3.0]);
+        let estimate = estimate_probabilities(&observations, &levels, EstimationMethod::NegativeBinomial, None).unwrap();
+        for (p, expected) in estimate.probabilities.iter().zip([0.25, 0.25, 3.0 / 16.0, 5.0 / 16.0]) {
+            assert!((p - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn poisson_with_large_mean_starts_from_mode() {
+        // λ = 10⁷, σ ≈ 3162: средний интервал ±500 близок к нормальному приближению
+        let levels = states(&[9_999_000.0, 10_000_000.0, 10_001_000.0]);
+        let estimate = estimate_probabilities(&[1e7], &levels, EstimationMethod::Poisson, None).unwrap();
+        let sigma = 1e7f64.sqrt();
+        let middle = normal_cdf(500.0 / sigma) - normal_cdf(-500.0 / sigma);
+        assert!((estimate.probabilities[1] - middle).abs() < 1e-3);
+        assert!((estimate.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,233 @@
+// -----------------------------
+// src/problems/imprecise_probabilities.rs
+// -----------------------------
+//! Критерии при неточных (интервальных) вероятностях состояний.
+//!
+//! Вероятности задаются интервалами [l_j, u_j] (`probability_bounds`); множество допустимых
+//! распределений (кредальное множество) — {p : l_j ≤ p_j ≤ u_j, Σ p_j = 1}. Нижнее и верхнее
+//! ожидание альтернативы — минимум и максимум Σ p_j·a_ij по этому множеству (задачи ЛП через `good_lp`).
+//! - gamma-maximin — лучшее гарантированное (нижнее для выплат) ожидание;
+//! - gamma-maximax — лучшее оптимистическое ожидание;
+//! - interval-dominance — исключаются альтернативы, у которых даже оптимистическое ожидание хуже
+//!   гарантированного ожидания другой альтернативы;
+//! - e-admissibility — остаются альтернативы, оптимальные по Байесу хотя бы при одном допустимом p.
+//!
+//! Если заданы точные вероятности, кредальное множество состоит из одной точки и все критерии
+//! совпадают с критерием Байеса. Для матрицы потерь (`orientation` = loss) формулы зеркальны.
+use crate::core::base::*;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+use good_lp::{constraint, default_solver, variable, variables, Expression, Solution, SolverModel};
+
+const EPS: f64 = 1e-9;
+
+
+pub struct ImpreciseProbabilitySolver;
+
+
+impl ImpreciseProbabilitySolver {
+    /// criterion: "gamma-maximin" | "gamma-maximax" | "interval-dominance" | "e-admissibility"
+    pub fn solve(spec: &ProblemSpec, criterion: &str) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let bounds = credal_set(spec)?;
+        let n_states = bounds.len();
+        if spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+        if spec.alternatives.iter().any(|a| a.values.len() != n_states) {
+            anyhow::bail!("Alternative values length must equal number of states");
+        }
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+        let states = spec.state_names(n_states);
+        let loss = spec.orientation == Orientation::Loss;
+        let sign = spec.orientation.sign();
+
+        // нижнее и верхнее ожидание в исходных единицах
+        let mut lower = Vec::new();
+        let mut upper = Vec::new();
+        for alt in &spec.alternatives {
+            lower.push(expectation(&alt.values, &bounds, false)?);
+            upper.push(expectation(&alt.values, &bounds, true)?);
+        }
+        // гарантированное и оптимистическое ожидание: для потерь это верхнее и нижнее
+        let (guaranteed, optimistic) = if loss { (&upper, &lower) } else { (&lower, &upper) };
+
+        let mut notes = Vec::new();
+        if loss {
+            notes.push("Значения матрицы — потери: гарантированное ожидание — верхнее, оптимистическое — нижнее".to_string());
+        }
+        let mut tables = vec![
+            ResultTable {
+                title: "Границы вероятностей состояний".to_string(),
+                columns: states.clone(),
+                rows: vec![
+                    ("нижняя".to_string(), bounds.iter().map(|b| b.0).collect()),
+                    ("верхняя".to_string(), bounds.iter().map(|b| b.1).collect()),
+                ],
+            },
+            ResultTable {
+                title: "Нижнее и верхнее ожидание по кредальному множеству".to_string(),
+                columns: vec!["нижнее M".to_string(), "верхнее M".to_string()],
+                rows: ids.iter().enumerate().map(|(i, id)| (id.clone(), vec![lower[i], upper[i]])).collect(),
+            },
+        ];
+
+        // оценки в исходных единицах; лучшая — наибольшая для выплат и наименьшая для потерь
+        let (values, chosen): (Vec<f64>, Vec<usize>) = match criterion {
+            "gamma-maximin" => (guaranteed.clone(), best(guaranteed, sign)),
+            "gamma-maximax" => (optimistic.clone(), best(optimistic, sign)),
+            "interval-dominance" => {
+                // i доминируется, если есть k, чьё гарантированное ожидание лучше оптимистического у i
+                let mut undominated = Vec::new();
+                for i in 0..ids.len() {
+                    let dominators: Vec<&str> = (0..ids.len())
+                        .filter(|&k| sign * (guaranteed[k] - optimistic[i]) > EPS)
+                        .map(|k| ids[k].as_str())
+                        .collect();
+                    if dominators.is_empty() {
+                        undominated.push(i);
+                    } else {
+                        notes.push(format!("{} интервально доминируется: {}", ids[i], dominators.join(", ")));
+                    }
+                }
+                (guaranteed.clone(), undominated)
+            },
+            "e-admissibility" => {
+                let gains: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.iter().map(|v| sign * v).collect()).collect();
+                let mut margins = Vec::new();
+                let mut witnesses = Vec::new();
+                for (i, id) in ids.iter().enumerate() {
+                    let (margin, p) = admissibility_margin(&gains, &bounds, i)?;
+                    margins.push(margin);
+                    if margin >= -EPS {
+                        witnesses.push((id.clone(), p));
+                    }
+                }
+                tables.push(ResultTable {
+                    title: "Распределения, при которых альтернатива оптимальна по Байесу".to_string(),
+                    columns: states,
+                    rows: witnesses,
+                });
+                notes.push("Оценка e-admissibility — запас: наибольшее по кредальному множеству превышение ожидания над лучшей из остальных (≥ 0 — допустима)".to_string());
+                let admissible = (0..ids.len()).filter(|&i| margins[i] >= -EPS).collect();
+                // запас уже выражен в «выплатах»: больше — лучше при любой ориентации
+                let mut scores: Vec<(String, f64)> = ids.iter().cloned().zip(margins).collect();
+                scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+                return Ok(DecisionResult {
+                    chosen: ids_of(&ids, admissible),
+                    scores,
+                    method: criterion.to_string(),
+                    tables,
+                    notes,
+                    ..Default::default()
+                });
+            },
+            _ => anyhow::bail!("Unknown criterion: {}", criterion),
+        };
+
+        let mut scores: Vec<(String, f64)> = ids.iter().cloned().zip(values).collect();
+        if loss {
+            scores.sort_by(|a, b| a.1.total_cmp(&b.1));
+        } else {
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        }
+        Ok(DecisionResult { chosen: ids_of(&ids, chosen), scores, method: criterion.to_string(), tables, notes, ..Default::default() })
+    }
+}
+
+
+/// Границы вероятностей: интервалы из задачи или вырожденные интервалы точных вероятностей
+pub fn credal_set(spec: &ProblemSpec) -> Result<Vec<(f64, f64)>> {
+    match (&spec.probability_bounds, &spec.state_probabilities) {
+        (Some(bounds), _) => Ok(bounds.clone()),
+        (None, Some(probs)) => Ok(probs.iter().map(|p| (*p, *p)).collect()),
+        (None, None) => anyhow::bail!("Probabilities or probability bounds required for imprecise criteria"),
+    }
+}
+
+
+/// Наименьшее (maximise = false) или наибольшее ожидание Σ p_j·values_j по кредальному множеству
+pub fn expectation(values: &[f64], bounds: &[(f64, f64)], maximise: bool) -> Result<f64> {
+    let mut vars = variables!();
+    let p: Vec<_> = bounds.iter().map(|(lo, hi)| vars.add(variable().min(*lo).max(*hi))).collect();
+    let objective: Expression = p.iter().zip(values.iter()).map(|(pj, v)| *v * *pj).sum();
+    let total: Expression = p.iter().sum();
+    let solution = if maximise {
+        vars.maximise(objective.clone()).using(default_solver).with(constraint!(total == 1.0)).solve()?
+    } else {
+        vars.minimise(objective.clone()).using(default_solver).with(constraint!(total == 1.0)).solve()?
+    };
+    Ok(p.iter().zip(values.iter()).map(|(pj, v)| solution.value(*pj) * v).sum())
+}
+
+
+/// Наибольший запас t, с которым альтернатива i лучше всех остальных при каком-либо допустимом p:
+/// max t при Σ_j p_j·(g_ij − g_kj) ≥ t для всех k ≠ i
+fn admissibility_margin(gains: &[Vec<f64>], bounds: &[(f64, f64)], i: usize) -> Result<(f64, Vec<f64>)> {
+    let mut vars = variables!();
+    let p: Vec<_> = bounds.iter().map(|(lo, hi)| vars.add(variable().min(*lo).max(*hi))).collect();
+    // при единственной альтернативе запас не ограничен — ограничиваем размахом выплат
+    let span = gains.iter().flatten().fold(0.0_f64, |m, g| m.max(g.abs())) * 2.0 + 1.0;
+    let t = vars.add(variable().max(span));
+    let mut model = vars.maximise(t).using(default_solver);
+    let total: Expression = p.iter().sum();
+    model = model.with(constraint!(total == 1.0));
+    for other in gains.iter().enumerate().filter(|(k, _)| *k != i).map(|(_, row)| row) {
+        let advantage: Expression = p.iter().enumerate().map(|(j, pj)| (gains[i][j] - other[j]) * *pj).sum();
+        model = model.with(constraint!(advantage >= t));
+    }
+    let solution = model.solve()?;
+    Ok((solution.value(t), p.iter().map(|pj| solution.value(*pj)).collect()))
+}
+
+
+/// Номера лучших значений: наибольших при sign = 1 и наименьших при sign = −1
+fn best(values: &[f64], sign: f64) -> Vec<usize> {
+    let top = values.iter().map(|v| sign * v).fold(f64::NEG_INFINITY, f64::max);
+    (0..values.len()).filter(|&i| sign * values[i] >= top - EPS * top.abs().max(1.0)).collect()
+}
+
+
+fn ids_of(ids: &[String], indices: Vec<usize>) -> Vec<String> {
+    indices.into_iter().map(|i| ids[i].clone()).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ProblemSpec {
+        serde_json::from_str(r#"{
+            "state_probabilities": [[0.2, 0.6], [0.4, 0.8]],
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [
+                {"id": "A", "values": [10, 0]},
+                {"id": "B", "values": [0, 10]},
+                {"id": "C", "values": [4.5, 4.5]}
+            ]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn gamma_maximin_takes_best_lower_expectation() {
+        // нижние ожидания: A — 10·0.2 = 2, B — 10·0.4 = 4, C — 4.5
+        let result = ImpreciseProbabilitySolver::solve(&spec(), "gamma-maximin").unwrap();
+        assert_eq!(result.chosen, vec!["C".to_string()]);
+        let score = |id: &str| result.scores.iter().find(|(a, _)| a == id).unwrap().1;
+        assert!((score("A") - 2.0).abs() < 1e-9);
+        assert!((score("B") - 4.0).abs() < 1e-9);
+        assert!((score("C") - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn e_admissibility_excludes_never_optimal_alternative() {
+        // C лучше A при p1 ≤ 0.45 и лучше B при p1 ≥ 0.55 — оптимальной не бывает;
+        // запасы: A — 1.5 при p1 = 0.6, B — 3.5 при p1 = 0.2, C — −0.5 при p1 = 0.5
+        let result = ImpreciseProbabilitySolver::solve(&spec(), "e-admissibility").unwrap();
+        assert_eq!(result.chosen, vec!["A".to_string(), "B".to_string()]);
+        let expected = [("B", 3.5), ("A", 1.5), ("C", -0.5)];
+        for ((id, margin), (expected_id, expected_margin)) in result.scores.iter().zip(expected) {
+            assert_eq!(id, expected_id);
+            assert!((margin - expected_margin).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// -----------------------------
+// src/problems/maut.rs
+// -----------------------------
+//! Многокритериальная теория полезности (MAUT).
+//!
+//! Для каждого критерия задаётся однокритериальная функция полезности (`Criterion.utility`,
+//! по умолчанию линейная на диапазоне данных), значения альтернатив переводятся в полезности
+//! u_i ∈ [0, 1] и агрегируются:
+//! - аддитивно: U = Σ k_i·u_i (k_i нормируются);
+//! - мультипликативно: 1 + K·U = Π (1 + K·k_i·u_i), где K находится из основного уравнения
+//!   1 + K = Π (1 + K·k_i).
+//!
+//! Шкалирующие константы k_i берутся из `Criterion.weight`.
+use crate::core::base::*;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+/// Предел удвоений правой границы при поиске корня основного уравнения (2^100 ≈ 1.3·10^30)
+const MAX_DOUBLINGS: usize = 100;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Additive,
+    Multiplicative,
+}
+
+
+impl Aggregation {
+    /// "additive" | "multiplicative"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "additive" => Ok(Aggregation::Additive),
+            "multiplicative" => Ok(Aggregation::Multiplicative),
+            _ => anyhow::bail!("Unknown aggregation: {}", name),
+        }
+    }
+}
+
+
+pub struct MautSolver;
+
+
+impl MautSolver {
+    pub fn solve(spec: &ProblemSpec, aggregation: Aggregation) -> Result<DecisionResult> {
+        ensure_complete(spec)?;
+        let m = spec.criteria.len();
+        if m == 0 || spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+        if spec.alternatives.iter().any(|a| a.values.len() != m) { anyhow::bail!("Alternative values length mismatch") }
+
+        // Полезности по критериям: rows = альтернативы, cols = критерии
+        let mut utilities = vec![vec![0.0; m]; spec.alternatives.len()];
+        for (j, crit) in spec.criteria.iter().enumerate() {
+            let col: Vec<f64> = spec.alternatives.iter().map(|a| a.values[j]).collect();
+            let lo = col.iter().cloned().fold(f64::INFINITY, f64::min);
+            let hi = col.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let maximize = crit.maximize.unwrap_or(true);
+            let default = UtilityFunction::Linear { min: None, max: None };
+            let func = crit.utility.as_ref().unwrap_or(&default);
+            for (row, x) in utilities.iter_mut().zip(col) {
+                row[j] = utility(func, x, lo, hi, maximize)?;
+            }
+        }
+
+        let k: Vec<f64> = spec.criteria.iter().map(|c| c.weight.unwrap_or(1.0)).collect();
+        if k.iter().any(|&ki| ki <= 0.0) {
+            anyhow::bail!("Шкалирующие константы должны быть положительными");
+        }
+
+        let mut notes = Vec::new();
+        let (scaling, big_k, method) = match aggregation {
+            Aggregation::Additive => {
+                let total: f64 = k.iter().sum();
+                (k.iter().map(|ki| ki / total).collect::<Vec<f64>>(), 0.0, "maut_additive".to_string())
+            }
+            // у уравнения 1 + K = 1 + K·k₁ нет ненулевого корня; полезность одного критерия и есть итоговая
+            Aggregation::Multiplicative if m == 1 => {
+                notes.push("Критерий один: мультипликативная форма совпадает с аддитивной (K = 0, k = 1)".to_string());
+                (vec![1.0], 0.0, "maut_multiplicative (K=0)".to_string())
+            }
+            Aggregation::Multiplicative => {
+                if k.iter().any(|&ki| ki >= 1.0) {
+                    anyhow::bail!("Для мультипликативной модели шкалирующие константы должны лежать в (0, 1)");
+                }
+                let big_k = master_equation_root(&k)?;
+                if !big_k.is_finite() {
+                    anyhow::bail!("Не удалось найти корень основного уравнения MAUT: K = {}", big_k);
+                }
+                let residual = (1.0 + big_k) - k.iter().map(|ki| 1.0 + big_k * ki).product::<f64>();
+                let method = format!(
+                    "maut_multiplicative (Σk={:.4}, K={:.6}, невязка основного уравнения={:.2e})",
+                    k.iter().sum::<f64>(), big_k, residual
+                );
+                (k, big_k, method)
+            }
+        };
+        // при K = 0 мультипликативная форма вырождается в аддитивную
+        let aggregate = |u: &[f64]| {
+            if big_k.abs() < 1e-12 {
+                u.iter().zip(scaling.iter()).map(|(ui, ki)| ui * ki).sum()
+            } else {
+                (u.iter().zip(scaling.iter()).map(|(ui, ki)| 1.0 + big_k * ki * ui).product::<f64>() - 1.0) / big_k
+            }
+        };
+
+        let mut scores: Vec<(String, f64)> = spec.alternatives.iter().zip(utilities.iter())
+            .map(|(alt, u)| (alt.id.clone(), aggregate(u)))
+            .collect();
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+
+        let criteria_ids: Vec<String> = spec.criteria.iter().map(|c| c.id.clone()).collect();
+        let table = ResultTable {
+            title: "Полезности по критериям".to_string(),
+            columns: criteria_ids.clone(),
+            rows: spec.alternatives.iter().map(|a| a.id.clone()).zip(utilities).collect(),
+        };
+
+        Ok(DecisionResult {
+            chosen,
+            scores,
+            method,
+            weights: Some(criteria_ids.into_iter().zip(scaling).collect()),
+            tables: vec![table],
+            notes,
+        })
+    }
+}
+
+
+/// Значение однокритериальной функции полезности; lo/hi — диапазон значений критерия в данных
+pub fn utility(func: &UtilityFunction, x: f64, lo: f64, hi: f64, maximize: bool) -> Result<f64> {
+    let normalized = |min: Option<f64>, max: Option<f64>| {
+        let (a, b) = (min.unwrap_or(lo), max.unwrap_or(hi));
+        if b <= a {
+            return 1.0;
+        }
+        let z = ((x - a) / (b - a)).clamp(0.0, 1.0);
+        if maximize { z } else { 1.0 - z }
+    };
+    Ok(match func {
+        UtilityFunction::Linear { min, max } => normalized(*min, *max),
+        UtilityFunction::Exponential { risk, min, max } => {
+            let z = normalized(*min, *max);
+            if risk.abs() < 1e-12 { z } else { (1.0 - (-risk * z).exp()) / (1.0 - (-risk).exp()) }
+        }
+        UtilityFunction::Piecewise { points } => {
+            if points.is_empty() {
+                anyhow::bail!("Кусочно-линейная полезность требует хотя бы одну точку");
+            }
+            let mut pts = points.clone();
+            pts.sort_by(|a, b| a.0.total_cmp(&b.0));
+            if x <= pts[0].0 {
+                pts[0].1
+            } else if x >= pts[pts.len() - 1].0 {
+                pts[pts.len() - 1].1
+            } else {
+                let i = pts.iter().position(|p| p.0 >= x).unwrap();
+                let ((x0, u0), (x1, u1)) = (pts[i - 1], pts[i]);
+                u0 + (u1 - u0) * (x - x0) / (x1 - x0)
+            }
+        }
+        UtilityFunction::Step { thresholds } => {
+            let mut steps = thresholds.clone();
+            steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+            steps.iter().rev().find(|(t, _)| *t <= x).map(|(_, u)| *u).unwrap_or(0.0)
+        }
+        UtilityFunction::Cara { .. } | UtilityFunction::Log { .. } | UtilityFunction::Crra { .. } => {
+            if hi <= lo {
+                return Ok(1.0);
+            }
+            let (u_lo, u_hi) = (money_utility(func, lo)?, money_utility(func, hi)?);
+            let z = (money_utility(func, x)? - u_lo) / (u_hi - u_lo);
+            if maximize { z } else { 1.0 - z }
+        }
+    })
+}
+
+
+/// Полезность денежной суммы для cara, log и crra (без нормировки); для остальных функций — x
+fn money_utility(func: &UtilityFunction, x: f64) -> Result<f64> {
+    let wealth = |shift: &Option<f64>| {
+        let w = x + shift.unwrap_or(0.0);
+        if w <= 0.0 {
+            anyhow::bail!("Логарифмическая и степенная полезности определены при x + shift > 0 (x = {})", x);
+        }
+        Ok(w)
+    };
+    Ok(match func {
+        UtilityFunction::Cara { a } if a.abs() < 1e-12 => x,
+        UtilityFunction::Cara { a } => (1.0 - (-a * x).exp()) / a,
+        UtilityFunction::Log { shift } => wealth(shift)?.ln(),
+        UtilityFunction::Crra { gamma, shift } => {
+            let w = wealth(shift)?;
+            if (gamma - 1.0).abs() < 1e-12 { w.ln() } else { (w.powf(1.0 - gamma) - 1.0) / (1.0 - gamma) }
+        }
+        _ => x,
+    })
+}
+
+
+/// Детерминированный эквивалент: x ∈ [lo, hi], при котором u(x) = target (бисекция; u не убывает)
+pub fn certainty_equivalent(func: &UtilityFunction, target: f64, lo: f64, hi: f64) -> Result<f64> {
+    let (mut a, mut b) = (lo, hi);
+    for _ in 0..200 {
+        let mid = 0.5 * (a + b);
+        if utility(func, mid, lo, hi, true)? < target { a = mid } else { b = mid }
+    }
+    Ok(0.5 * (a + b))
+}
+
+
+/// Ненулевой корень K > −1 уравнения 1 + K = Π (1 + K·k_i) (бисекция).
+/// При Σk = 1 модель аддитивна и K = 0; при Σk < 1 корень K > 0, при Σk > 1 — K ∈ (−1, 0).
+/// Нужны хотя бы два критерия: при одном ненулевого корня нет.
+fn master_equation_root(k: &[f64]) -> Result<f64> {
+    let sum: f64 = k.iter().sum();
+    if (sum - 1.0).abs() < 1e-9 {
+        return Ok(0.0);
+    }
+    let f = |big_k: f64| k.iter().map(|ki| 1.0 + big_k * ki).product::<f64>() - 1.0 - big_k;
+    let (mut a, mut b) = if sum > 1.0 {
+        (-1.0 + 1e-12, -1e-12)
+    } else {
+        // правую границу удваиваем, пока f не станет положительной; при очень малых k корень может быть огромным
+        let mut hi = 1.0;
+        for _ in 0..MAX_DOUBLINGS {
+            if f(hi) >= 0.0 {
+                break;
+            }
+            hi *= 2.0;
+        }
+        if f(hi).is_nan() || f(hi) < 0.0 {
+            anyhow::bail!(
+                "Корень основного уравнения MAUT не найден на (0, {:e}]: шкалирующие константы (Σk = {}) слишком малы",
+                hi, sum
+            );
+        }
+        (1e-12, hi)
+    };
+    for _ in 0..200 {
+        let mid = 0.5 * (a + b);
+        if (f(a) < 0.0) == (f(mid) < 0.0) { a = mid } else { b = mid }
+    }
+    Ok(0.5 * (a + b))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn master_equation_known_roots() {
+        // (1 + 0.4K)² = 1 + K  ⇒  0.16K² = 0.2K  ⇒  K = 1.25
+        assert!((master_equation_root(&[0.4, 0.4]).unwrap() - 1.25).abs() < 1e-9);
+        // (1 + 0.6K)² = 1 + K  ⇒  0.36K² = −0.2K  ⇒  K = −5/9
+        assert!((master_equation_root(&[0.6, 0.6]).unwrap() + 5.0 / 9.0).abs() < 1e-9);
+        assert_eq!(master_equation_root(&[0.5, 0.5]).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn multiplicative_single_criterion_is_additive() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{"id": "c", "weight": 0.3}],
+            "alternatives": [
+                {"id": "A1", "values": [1]},
+                {"id": "A2", "values": [3]}
+            ]
+        }"#).unwrap();
+        let result = MautSolver::solve(&spec, Aggregation::Multiplicative).unwrap();
+        assert_eq!(result.chosen, vec!["A2".to_string()]);
+        assert!((result.scores[0].1 - 1.0).abs() < 1e-12);
+        assert!(result.scores[1].1.abs() < 1e-12);
+    }
+}
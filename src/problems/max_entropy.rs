@@ -0,0 +1,307 @@
+// -----------------------------
+// src/problems/max_entropy.rs
+// -----------------------------
+//! Оценка вероятностей состояний по принципу максимума энтропии.
+//!
+//! Когда о состояниях известно немногое (вероятности отдельных состояний, ожидаемый спрос,
+//! порядок состояний по правдоподобию, интервалы вероятностей — `ProbabilityConstraints`
+//! и интервальные `state_probabilities`), из всех согласованных с этим распределений выбирается
+//! наименее «придуманное» — с наибольшей энтропией H = −Σ p_j·ln p_j. Без ограничений это
+//! равномерное распределение, при известном среднем — p_j ∝ e^{−λ·x_j}.
+//!
+//! Решение ищется через двойственную задачу: p_j ∝ exp(−Σ_k λ_k·c_kj), где λ_k — множители
+//! ограничений (для неравенств λ_k ≥ 0), а выпуклая функция ln Σ_j exp(−Σ_k λ_k·c_kj) минимизируется
+//! проекционным градиентным спуском. Состояния, которым ограничения оставляют только нулевую
+//! вероятность, находятся заранее задачами ЛП — они же проверяют совместность ограничений.
+use crate::core::base::*;
+use anyhow::Result;
+use good_lp::{constraint, default_solver, variable, variables, Expression, ResolutionError, Solution, SolverModel};
+
+const EPS: f64 = 1e-9;
+const TOLERANCE: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 200_000;
+
+
+/// Оценка: вероятности по состояниям, энтропия, проверка ограничений и пояснения
+pub struct MaxEntropy {
+    pub probabilities: Vec<(String, f64)>,
+    pub entropy: f64,
+    pub constraints: ResultTable,
+    pub notes: Vec<String>,
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum Relation {
+    Equal,
+    AtMost,
+    AtLeast,
+}
+
+
+/// Линейное ограничение Σ_j coefficients_j·p_j (=, ≤ или ≥) target
+struct Row {
+    label: String,
+    coefficients: Vec<f64>,
+    target: f64,
+    relation: Relation,
+}
+
+
+/// Распределение максимальной энтропии при ограничениях из задачи
+pub fn max_entropy(spec: &ProblemSpec) -> Result<MaxEntropy> {
+    if spec.state_probabilities.is_some() {
+        anyhow::bail!("Вероятности состояний уже заданы числами — оценивать нечего");
+    }
+    let n = spec.alternatives.first().map_or(spec.criteria.len(), |a| a.values.len());
+    if n == 0 {
+        anyhow::bail!("Нет состояний для оценки вероятностей");
+    }
+    let names = spec.state_names(n);
+    let rows = constraint_rows(spec, &names)?;
+    let support = support(&rows, n)?;
+    let (p, multipliers, residual) = solve_dual(&rows, &support);
+    let mut notes = Vec::new();
+    if residual > TOLERANCE {
+        notes.push(format!(
+            "Двойственная задача не сошлась за {} итераций (невязка {:.2e}): распределение приближённое",
+            MAX_ITERATIONS, residual
+        ));
+    }
+
+    let entropy = -p.iter().filter(|v| **v > 0.0).map(|v| v * v.ln()).sum::<f64>();
+    let constraints = ResultTable {
+        title: format!("Ограничения (максимум энтропии ln {} = {:.4})", n, (n as f64).ln()),
+        columns: vec!["значение".to_string(), "граница".to_string(), "множитель λ".to_string()],
+        rows: rows.iter().zip(multipliers)
+            .map(|(row, lambda)| {
+                // остаток итераций на активных ограничениях не показываем
+                let value = dot(&row.coefficients, &p);
+                let value = if (value - row.target).abs() < EPS { row.target } else { value };
+                (row.label.clone(), vec![value, row.target, lambda])
+            })
+            .collect(),
+    };
+    Ok(MaxEntropy { probabilities: names.into_iter().zip(p).collect(), entropy, constraints, notes })
+}
+
+
+/// Записывает оценку в `state_probabilities`, если в задаче заданы `probability_constraints`,
+/// а точных вероятностей нет; интервалы вероятностей при этом заменяются точечной оценкой
+pub fn apply_max_entropy(spec: &mut ProblemSpec) -> Result<()> {
+    if spec.probability_constraints.is_none() {
+        return Ok(());
+    }
+    if spec.state_probabilities.is_some() {
+        spec.notes.push("Вероятности состояний заданы числами — ограничения probability_constraints не используются".to_string());
+        return Ok(());
+    }
+    let estimate = max_entropy(spec)?;
+    let listed: Vec<String> = estimate.probabilities.iter().map(|(s, p)| format!("{} = {:.4}", s, p)).collect();
+    spec.notes.push(format!(
+        "Вероятности состояний оценены по максимуму энтропии (H = {:.4}): {}",
+        estimate.entropy, listed.join(", ")
+    ));
+    spec.notes.extend(estimate.notes);
+    if spec.probability_bounds.take().is_some() {
+        spec.notes.push("Интервалы вероятностей заменены распределением максимальной энтропии внутри них".to_string());
+    }
+    spec.state_probabilities = Some(estimate.probabilities.into_iter().map(|(_, p)| p).collect());
+    Ok(())
+}
+
+
+/// Ограничения из `probability_constraints` и границ интервальных вероятностей
+fn constraint_rows(spec: &ProblemSpec, names: &[String]) -> Result<Vec<Row>> {
+    let n = names.len();
+    let index = |name: &str| names.iter().position(|s| s == name)
+        .ok_or_else(|| anyhow::anyhow!("Неизвестное состояние в ограничениях на вероятности: {}", name));
+    let unit = |j: usize| -> Vec<f64> { (0..n).map(|k| if k == j { 1.0 } else { 0.0 }).collect() };
+    let mut rows = Vec::new();
+
+    if let Some(bounds) = &spec.probability_bounds {
+        if bounds.len() != n {
+            anyhow::bail!("Число интервалов вероятностей должно совпадать с числом состояний");
+        }
+        for (j, &(lo, hi)) in bounds.iter().enumerate() {
+            if lo == hi {
+                rows.push(Row { label: format!("P({}) = {}", names[j], lo), coefficients: unit(j), target: lo, relation: Relation::Equal });
+                continue;
+            }
+            if lo > 0.0 {
+                rows.push(Row { label: format!("P({}) ≥ {}", names[j], lo), coefficients: unit(j), target: lo, relation: Relation::AtLeast });
+            }
+            if hi < 1.0 {
+                rows.push(Row { label: format!("P({}) ≤ {}", names[j], hi), coefficients: unit(j), target: hi, relation: Relation::AtMost });
+            }
+        }
+    }
+
+    let Some(known) = &spec.probability_constraints else { return Ok(rows) };
+    let mut fixed: Vec<(usize, f64)> = known.fixed.iter().map(|(s, p)| Ok((index(s)?, *p))).collect::<Result<_>>()?;
+    fixed.sort_by_key(|(j, _)| *j);
+    for (j, p) in fixed {
+        if !(0.0..=1.0).contains(&p) {
+            anyhow::bail!("Вероятность состояния {} должна лежать в [0, 1]: {}", names[j], p);
+        }
+        rows.push(Row { label: format!("P({}) = {}", names[j], p), coefficients: unit(j), target: p, relation: Relation::Equal });
+    }
+    if let Some(mean) = known.mean {
+        let values = spec.state_values(n)
+            .ok_or_else(|| anyhow::anyhow!("Для ограничения на среднее нужны значения состояний (state_values) или числа в их названиях"))?;
+        if values.len() != n {
+            anyhow::bail!("Число значений состояний (state_values) должно совпадать с числом состояний");
+        }
+        rows.push(Row { label: format!("M[значение состояния] = {}", mean), coefficients: values, target: mean, relation: Relation::Equal });
+    }
+    for chain in &known.order {
+        for pair in chain.windows(2) {
+            let (a, b) = (index(&pair[0])?, index(&pair[1])?);
+            let mut coefficients = unit(a);
+            coefficients[b] -= 1.0;
+            rows.push(Row { label: format!("P({}) − P({}) ≥ 0", names[a], names[b]), coefficients, target: 0.0, relation: Relation::AtLeast });
+        }
+    }
+    Ok(rows)
+}
+
+
+/// Состояния, которым ограничения допускают ненулевую вероятность (max p_j > 0 по ЛП);
+/// несовместные ограничения — ошибка
+fn support(rows: &[Row], n: usize) -> Result<Vec<bool>> {
+    let mut support = Vec::with_capacity(n);
+    for j in 0..n {
+        let mut vars = variables!();
+        let p: Vec<_> = (0..n).map(|_| vars.add(variable().min(0.0).max(1.0))).collect();
+        let total: Expression = p.iter().sum();
+        let mut model = vars.maximise(p[j]).using(default_solver).with(constraint!(total == 1.0));
+        for row in rows {
+            let lhs: Expression = row.coefficients.iter().zip(p.iter()).map(|(c, pj)| *c * *pj).sum();
+            model = match row.relation {
+                Relation::Equal => model.with(constraint!(lhs == row.target)),
+                Relation::AtMost => model.with(constraint!(lhs <= row.target)),
+                Relation::AtLeast => model.with(constraint!(lhs >= row.target)),
+            };
+        }
+        match model.solve() {
+            Ok(solution) => support.push(solution.value(p[j]) > EPS),
+            Err(ResolutionError::Infeasible) => anyhow::bail!("Ограничения на вероятности несовместны: ни одно распределение им не удовлетворяет"),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(support)
+}
+
+
+/// Минимизация двойственной функции по λ (λ_k ≥ 0 для неравенств, приведённых к виду ≤) на состояниях из support;
+/// возвращает распределение по всем состояниям, множители в единицах исходных ограничений
+/// и итоговую невязку (больше TOLERANCE — итерации исчерпаны)
+fn solve_dual(rows: &[Row], support: &[bool]) -> (Vec<f64>, Vec<f64>, f64) {
+    let columns: Vec<usize> = (0..support.len()).filter(|&j| support[j]).collect();
+    // с учётом Σ p_j = 1 ограничение Σ a_j·p_j ~ b равносильно Σ (a_j − b)·p_j ~ 0; нормируем на max |a_j − b|,
+    // неравенства ≥ меняют знак
+    let scales: Vec<f64> = rows.iter()
+        .map(|r| columns.iter().map(|&j| (r.coefficients[j] - r.target).abs()).fold(0.0, f64::max))
+        .collect();
+    let c: Vec<Vec<f64>> = rows.iter().zip(scales.iter())
+        .map(|(r, &s)| {
+            let sign = if r.relation == Relation::AtLeast { -1.0 } else { 1.0 };
+            columns.iter().map(|&j| if s > EPS { sign * (r.coefficients[j] - r.target) / s } else { 0.0 }).collect()
+        })
+        .collect();
+    let project = |k: usize, v: f64| if rows[k].relation == Relation::Equal { v } else { v.max(0.0) };
+    // значение двойственной функции и распределение при множителях lambda
+    let evaluate = |lambda: &[f64]| -> (f64, Vec<f64>) {
+        let exponents: Vec<f64> = (0..columns.len())
+            .map(|j| -lambda.iter().zip(c.iter()).map(|(l, row)| l * row[j]).sum::<f64>())
+            .collect();
+        let top = exponents.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = exponents.iter().map(|e| (e - top).exp()).collect();
+        let z: f64 = weights.iter().sum();
+        (top + z.ln(), weights.into_iter().map(|w| w / z).collect())
+    };
+
+    let mut lambda = vec![0.0; rows.len()];
+    let (mut value, mut p) = evaluate(&lambda);
+    let mut step = 1.0;
+    // градиент: −M[c_k]; невязка — длина шага проекционного градиента
+    let residual_at = |lambda: &[f64], p: &[f64]| -> (Vec<f64>, f64) {
+        let gradient: Vec<f64> = c.iter().map(|row| -dot(row, p)).collect();
+        let residual = (0..rows.len()).map(|k| (lambda[k] - project(k, lambda[k] - gradient[k])).abs()).fold(0.0, f64::max);
+        (gradient, residual)
+    };
+    for _ in 0..MAX_ITERATIONS {
+        let (gradient, residual) = residual_at(&lambda, &p);
+        if residual < TOLERANCE {
+            break;
+        }
+        // шаг с возвратом: квадратичная оценка сверху должна выполняться
+        loop {
+            let candidate: Vec<f64> = (0..rows.len()).map(|k| project(k, lambda[k] - step * gradient[k])).collect();
+            let (next_value, next_p) = evaluate(&candidate);
+            let delta: Vec<f64> = candidate.iter().zip(lambda.iter()).map(|(a, b)| a - b).collect();
+            if next_value <= value + dot(&gradient, &delta) + dot(&delta, &delta) / (2.0 * step) + EPS * EPS || step < EPS {
+                lambda = candidate;
+                value = next_value;
+                p = next_p;
+                step *= 2.0;
+                break;
+            }
+            step /= 2.0;
+        }
+    }
+
+    let (_, residual) = residual_at(&lambda, &p);
+
+    let mut full = vec![0.0; support.len()];
+    for (&j, pj) in columns.iter().zip(p) {
+        full[j] = pj;
+    }
+    let multipliers = lambda.iter().zip(scales.iter()).map(|(l, &s)| if s > EPS { l / s } else { 0.0 }).collect();
+    (full, multipliers, residual)
+}
+
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(json: &str) -> ProblemSpec {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn known_mean_gives_geometric_distribution() {
+        // p_j ∝ e^{−λ·x_j} на x = 0, 1, 2; при e^{−λ} = 1/2 p = (4, 2, 1)/7 и M[x] = 4/7
+        let spec = spec(r#"{
+            "probability_constraints": {"state_values": [0, 1, 2], "mean": 0.5714285714285714},
+            "criteria": [{"id": "S0"}, {"id": "S1"}, {"id": "S2"}],
+            "alternatives": [{"id": "A", "values": [1, 2, 3]}]
+        }"#);
+        let estimate = max_entropy(&spec).unwrap();
+        assert!(estimate.notes.is_empty());
+        for ((_, p), expected) in estimate.probabilities.iter().zip([4.0 / 7.0, 2.0 / 7.0, 1.0 / 7.0]) {
+            assert!((p - expected).abs() < 1e-7);
+        }
+        // множитель среднего λ = ln 2
+        assert!((estimate.constraints.rows[0].1[2] - 2f64.ln()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn numeric_probabilities_are_kept() {
+        let mut spec = spec(r#"{
+            "state_probabilities": [0.2, 0.8],
+            "probability_constraints": {"mean": 1.5},
+            "criteria": [{"id": "S1"}, {"id": "S2"}],
+            "alternatives": [{"id": "A", "values": [1, 2]}]
+        }"#);
+        apply_max_entropy(&mut spec).unwrap();
+        assert_eq!(spec.state_probabilities, Some(vec![0.2, 0.8]));
+        assert_eq!(spec.notes.len(), 1);
+    }
+}
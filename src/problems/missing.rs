@@ -0,0 +1,211 @@
+// -----------------------------
+// src/problems/missing.rs
+// -----------------------------
+//! Обработка пропущенных значений в матрице решений.
+//!
+//! Неизвестное значение задаётся во входном файле как null (в `values` хранится NaN).
+//! Перед запуском решателя выбирается политика:
+//! - exclude-alternative — исключить альтернативы с пропусками;
+//! - exclude-criterion — исключить критерии с пропусками;
+//! - impute-mean | impute-median | impute-worst | impute-best — заполнить пропуск
+//!   средним, медианой, худшим или лучшим известным значением критерия;
+//! - renormalize — оставить пропуски, а веса перенормировать по известным критериям
+//!   (поддерживается только взвешенной суммой).
+//!
+//! Каждое применённое действие записывается в `ProblemSpec.notes`.
+use crate::core::base::*;
+use anyhow::Result;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Imputation {
+    Mean,
+    Median,
+    Worst,
+    Best,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissingPolicy {
+    ExcludeAlternative,
+    ExcludeCriterion,
+    Impute(Imputation),
+    Renormalize,
+}
+
+
+impl MissingPolicy {
+    pub fn from_name(name: &str) -> Result<Self> {
+        Ok(match name {
+            "exclude-alternative" => MissingPolicy::ExcludeAlternative,
+            "exclude-criterion" => MissingPolicy::ExcludeCriterion,
+            "impute-mean" => MissingPolicy::Impute(Imputation::Mean),
+            "impute-median" => MissingPolicy::Impute(Imputation::Median),
+            "impute-worst" => MissingPolicy::Impute(Imputation::Worst),
+            "impute-best" => MissingPolicy::Impute(Imputation::Best),
+            "renormalize" => MissingPolicy::Renormalize,
+            _ => anyhow::bail!("Unknown missing value policy: {}", name),
+        })
+    }
+}
+
+
+/// Применяет политику к задаче; после всех политик, кроме renormalize, пропусков не остаётся
+pub fn apply_missing_policy(spec: &mut ProblemSpec, policy: MissingPolicy) -> Result<()> {
+    if spec.alternatives.iter().all(|a| a.missing().is_empty()) {
+        return Ok(());
+    }
+    match policy {
+        MissingPolicy::ExcludeAlternative => {
+            let (kept, dropped): (Vec<Alternative>, Vec<Alternative>) =
+                spec.alternatives.drain(..).partition(|a| a.missing().is_empty());
+            for alt in &dropped {
+                spec.notes.push(format!("Исключена альтернатива {} (нет значений: {})", alt.id, criteria_names(spec, &alt.missing())));
+            }
+            if kept.is_empty() {
+                anyhow::bail!("После исключения альтернатив с пропусками не осталось ни одной");
+            }
+            spec.alternatives = kept;
+        }
+        MissingPolicy::ExcludeCriterion => {
+            let mut dropped: Vec<usize> = spec.alternatives.iter().flat_map(|a| a.missing()).collect();
+            dropped.sort();
+            dropped.dedup();
+            spec.notes.push(format!("Исключены критерии с пропусками: {}", criteria_names(spec, &dropped)));
+            if dropped.len() == spec.criteria.len() {
+                anyhow::bail!("Пропуски есть по всем критериям");
+            }
+            for &j in dropped.iter().rev() {
+                spec.criteria.remove(j);
+                for alt in &mut spec.alternatives {
+                    alt.values.remove(j);
+                    alt.cells.remove(j);
+                    alt.labels.remove(j);
+                }
+                // столбцы риск-задач — состояния: их вероятности тоже исключаются
+                if let Some(probs) = &mut spec.state_probabilities
+                    && probs.len() > j
+                {
+                    probs.remove(j);
+                }
+                if let Some(bounds) = &mut spec.probability_bounds
+                    && bounds.len() > j
+                {
+                    bounds.remove(j);
+                }
+                if let Some(values) = spec.probability_constraints.as_mut().and_then(|c| c.state_values.as_mut())
+                    && values.len() > j
+                {
+                    values.remove(j);
+                }
+                if let Some(likelihood) = &mut spec.likelihood {
+                    likelihood.matrix.iter_mut().filter(|row| row.len() > j).for_each(|row| { row.remove(j); });
+                }
+            }
+            if let Some(probs) = &mut spec.state_probabilities {
+                let total: f64 = probs.iter().sum();
+                probs.iter_mut().for_each(|p| *p /= total);
+                spec.notes.push("Вероятности оставшихся состояний перенормированы".to_string());
+            }
+            if let Some(bounds) = &spec.probability_bounds {
+                // сумма верхних границ после удаления могла стать меньше 1 — кредальное множество пусто
+                let lower: f64 = bounds.iter().map(|(lo, _)| lo).sum();
+                let upper: f64 = bounds.iter().map(|(_, hi)| hi).sum();
+                if lower > 1.0 + 1e-9 || upper < 1.0 - 1e-9 {
+                    anyhow::bail!(
+                        "После исключения состояний интервалы вероятностей несовместны: сумма нижних границ {}, сумма верхних {}. \
+                         Заполните пропуски (impute-*) или исключите альтернативы (exclude-alternative)",
+                        lower, upper
+                    );
+                }
+                spec.notes.push("Границы вероятностей исключённых состояний удалены, границы остальных не менялись".to_string());
+            }
+            if spec.hierarchy.take().is_some() {
+                spec.notes.push("Дерево критериев не используется: часть листьев исключена".to_string());
+            }
+        }
+        MissingPolicy::Impute(how) => {
+            for j in 0..spec.criteria.len() {
+                let mut known: Vec<f64> = spec.alternatives.iter().map(|a| a.values[j]).filter(|v| !v.is_nan()).collect();
+                if known.len() == spec.alternatives.len() {
+                    continue;
+                }
+                if known.is_empty() {
+                    anyhow::bail!("Критерий {}: нет ни одного известного значения", spec.criteria[j].id);
+                }
+                known.sort_by(|a, b| a.total_cmp(b));
+                let maximize = spec.criteria[j].maximize.unwrap_or(true);
+                let (value, name) = match how {
+                    Imputation::Mean => (known.iter().sum::<f64>() / known.len() as f64, "средним"),
+                    Imputation::Median => {
+                        let k = known.len();
+                        let med = if k % 2 == 1 { known[k / 2] } else { (known[k / 2 - 1] + known[k / 2]) / 2.0 };
+                        (med, "медианой")
+                    }
+                    Imputation::Worst => (if maximize { known[0] } else { known[known.len() - 1] }, "худшим значением"),
+                    Imputation::Best => (if maximize { known[known.len() - 1] } else { known[0] }, "лучшим значением"),
+                };
+                for alt in spec.alternatives.iter_mut().filter(|a| a.values[j].is_nan()) {
+                    alt.values[j] = value;
+                    alt.cells[j] = CellValue::Crisp(value);
+                    spec.notes.push(format!("{} / {}: пропуск заполнен {} ({})", alt.id, spec.criteria[j].id, name, value));
+                }
+            }
+        }
+        MissingPolicy::Renormalize => {
+            for alt in spec.alternatives.iter().filter(|a| !a.missing().is_empty()) {
+                spec.notes.push(format!(
+                    "{}: веса перенормированы по известным критериям (нет значений: {})",
+                    alt.id, criteria_names(spec, &alt.missing())
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+
+/// Ошибка с перечнем пропусков, если решатель не умеет с ними работать
+pub fn ensure_complete(spec: &ProblemSpec) -> Result<()> {
+    let cells: Vec<String> = spec.alternatives.iter()
+        .filter(|a| !a.missing().is_empty())
+        .map(|a| format!("{} ({})", a.id, criteria_names(spec, &a.missing())))
+        .collect();
+    if !cells.is_empty() {
+        anyhow::bail!(
+            "В задаче есть пропущенные значения: {}. Выберите политику --missing: exclude-alternative, exclude-criterion, \
+             impute-mean, impute-median, impute-worst, impute-best (renormalize — только для взвешенной суммы)",
+            cells.join("; ")
+        );
+    }
+    Ok(())
+}
+
+
+fn criteria_names(spec: &ProblemSpec, columns: &[usize]) -> String {
+    columns.iter().map(|&j| spec.criteria.get(j).map_or("?", |c| c.id.as_str())).collect::<Vec<_>>().join(", ")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::expected_utility::ExpectedUtilitySolver;
+    use crate::problems::stochastic_dominance::{StochasticDominanceSolver, DominanceOrder};
+
+    #[test]
+    fn solvers_without_gap_support_reject_missing_values() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "s1" }, { "id": "s2" }],
+            "alternatives": [
+                { "id": "A", "values": [1, null] },
+                { "id": "B", "values": [2, 3] }
+            ],
+            "state_probabilities": [0.5, 0.5]
+        }"#).unwrap();
+        let err = StochasticDominanceSolver::solve(&spec, DominanceOrder::First).unwrap_err();
+        assert!(err.to_string().contains("A (s2)"));
+        assert!(ExpectedUtilitySolver::solve(&spec).is_err());
+    }
+}
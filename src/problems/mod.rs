@@ -5,4 +5,21 @@ pub mod nutrition;
 pub mod clustering;
 pub mod decision_rules;
 pub mod bayes_rules;
-pub mod ahp;
\ No newline at end of file
+pub mod bayes_update;
+pub mod decision_tree;
+pub mod expected_utility;
+pub mod stochastic_dominance;
+pub mod parameter_sweep;
+pub mod criteria_consensus;
+pub mod probability_sensitivity;
+pub mod imprecise_probabilities;
+pub mod max_entropy;
+pub mod history_estimation;
+pub mod ahp;
+pub mod anp;
+pub mod bwm;
+pub mod weighting;
+pub mod maut;
+pub mod choquet;
+pub mod fuzzy;
+pub mod missing;
@@ -5,8 +5,16 @@
 //!
 //! В этом модуле реализован `WeightedSumSolver`, который вычисляет
 //! итоговую оценку альтернатив как взвешенную сумму значений по критериям.
-//! Веса нормализуются так, чтобы сумма была равна 1.
+//! Значения каждого критерия предварительно приводятся к [0, 1] min-max с учётом направления
+//! (1 — лучшее значение, в том числе для `maximize: false`), чтобы критерии в разных единицах
+//! были соизмеримы. Веса нормализуются так, чтобы сумма была равна 1.
+//! Если критерии заданы деревом, используются глобальные веса листьев,
+//! а в результат добавляются оценки по каждой подцели.
+//! Пропущенные значения (NaN) не учитываются: веса альтернативы перенормируются
+//! по известным критериям (политика renormalize из `missing`); альтернативы без известных
+//! значений не оцениваются.
 use crate::core::base::*;
+use crate::problems::weighting::normalized_columns;
 use anyhow::Result;
 
 
@@ -24,16 +32,85 @@ impl WeightedSumSolver {
         let weights: Vec<f64> = weights.into_iter().map(|w| w / sw).collect();
 
 
+        if spec.alternatives.iter().any(|a| a.values.len() != m) { anyhow::bail!("Alternative values length mismatch") }
+        // нормированные значения по альтернативам; пропуски (NaN) остаются пропусками
+        let columns = normalized_columns(spec);
+        let normalized: Vec<Vec<f64>> = (0..spec.alternatives.len())
+            .map(|i| columns.iter().map(|col| col[i]).collect())
+            .collect();
+
         let mut scores: Vec<(String, f64)> = Vec::new();
-        for alt in &spec.alternatives {
-            if alt.values.len() != m { anyhow::bail!("Alternative values length mismatch") }
-            let s = alt.values.iter().enumerate().map(|(i,v)| v * weights[i]).sum();
+        let mut scored = Vec::new();
+        let mut notes = Vec::new();
+        for (alt, values) in spec.alternatives.iter().zip(normalized.iter()) {
+            let s = known_weighted_mean(values.iter().cloned().zip(weights.iter().cloned()));
+            // при renormalize у альтернативы может не остаться ни одного известного значения с ненулевым весом
+            if s.is_nan() {
+                notes.push(format!("{}: нет известных значений по критериям с ненулевым весом — альтернатива не оценивается", alt.id));
+                continue;
+            }
             scores.push((alt.id.clone(), s));
+            scored.push((alt, values));
+        }
+        if scores.is_empty() {
+            anyhow::bail!("Ни одну альтернативу нельзя оценить: нет известных значений по критериям с ненулевым весом");
         }
-        scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
         let best = scores[0].1;
         let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
 
-        Ok(DecisionResult{chosen, scores, method: "multicriteria_weighted_sum".to_string()})
+        // Для дерева критериев — оценки альтернатив по каждой подцели: текущие веса листьев
+        // (в том числе заданные --weights или BWM), нормированные внутри ветви
+        let branches = spec.branches();
+        let mut tables = Vec::new();
+        if !branches.is_empty() {
+            tables.push(ResultTable {
+                title: "Оценки по подцелям".to_string(),
+                columns: branches.iter().map(|(id, _)| id.clone()).collect(),
+                rows: scored.iter().map(|(alt, normalized)| {
+                    let values: Vec<f64> = branches.iter()
+                        .map(|(_, leaves)| known_weighted_mean(leaves.iter().map(|i| (normalized[*i], weights[*i]))))
+                        .collect();
+                    for ((branch, _), v) in branches.iter().zip(values.iter()) {
+                        if v.is_nan() {
+                            notes.push(format!("{} / {}: нет известных значений, оценка по подцели не определена", alt.id, branch));
+                        }
+                    }
+                    (alt.id.clone(), values)
+                }).collect(),
+            });
+        }
+
+        let weights = spec.criteria.iter().map(|c| c.id.clone()).zip(weights).collect();
+        Ok(DecisionResult{chosen, scores, method: "multicriteria_weighted_sum".to_string(), weights: Some(weights), tables, notes})
+    }
+}
+
+
+/// Σ v·w / Σ w по известным значениям; при полных данных и Σ w = 1 — обычная взвешенная сумма
+fn known_weighted_mean(pairs: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let (sum, total) = pairs.filter(|(v, _)| !v.is_nan())
+        .fold((0.0, 0.0), |(s, t), (v, w)| (s + v * w, t + w));
+    if total > 0.0 { sum / total } else { f64::NAN }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::read_spec;
+
+    #[test]
+    fn tree_example_is_normalized_by_direction() {
+        let spec = read_spec("data/headphones_tree.json").unwrap();
+        let result = WeightedSumSolver::solve(&spec).unwrap();
+        // SONY: самая низкая цена и наибольшая частота (по 1), чувствительность 10/26, вес (273 − 245)/92, кабель 0
+        let sony = 0.4 + 0.2 * 10.0 / 26.0 + 0.2 + 0.1 * 28.0 / 92.0;
+        assert_eq!(result.chosen, vec!["SONY MDR-XB950AP BLACK".to_string()]);
+        assert!((result.scores[0].1 - sony).abs() < 1e-9);
+        // подцель «Комфорт»: SHURE легче всех и с самым длинным кабелем
+        let comfort = &result.tables[0];
+        let shure = comfort.rows.iter().find(|(id, _)| id == "SHURE SRH 440").unwrap();
+        assert!((shure.1[1] - 1.0).abs() < 1e-12);
     }
 }
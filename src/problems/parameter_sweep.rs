@@ -0,0 +1,264 @@
+// -----------------------------
+// src/problems/parameter_sweep.rs
+// -----------------------------
+//! Анализ критериев Гурвица, Ферстнера и Ходжа-Лемана по всему диапазону параметра.
+//!
+//! Оценка каждой альтернативы линейна по параметру t (α Гурвица или λ Ферстнера и Ходжа-Лемана):
+//! s_i(t) = s_i(lo) + (t − lo)·k_i, поэтому достаточно двух вызовов решателя на концах отрезка.
+//! Оптимальная альтернатива — верхняя (для потерь и сожалений — нижняя) огибающая прямых,
+//! и точки переключения находятся точно среди попарных пересечений прямых, без перебора по сетке.
+//! Результат — области оптимальности, таблица оценок в узлах и текстовый график s_i(t).
+use crate::core::base::*;
+use crate::problems::bayes_rules::ProbabilisticRulesSolver;
+use crate::problems::decision_rules::DecisionRulesSolver;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+const EPS: f64 = 1e-9;
+const PLOT_WIDTH: usize = 61;
+const PLOT_HEIGHT: usize = 20;
+
+// решатель критерия с параметром: DecisionRulesSolver::solve или ProbabilisticRulesSolver::solve
+type RuleSolver = fn(&ProblemSpec, &str, Option<f64>) -> Result<DecisionResult>;
+
+
+/// Результат анализа для печати и текстовый график оценок от параметра
+pub struct Sweep {
+    pub result: DecisionResult,
+    pub plot: String,
+}
+
+
+pub struct ParameterSweepSolver;
+
+
+impl ParameterSweepSolver {
+    /// criterion: "hurwicz" | "hurwicz-regret" (α, `DecisionRulesSolver`)
+    /// | "ferstner" | "hodge-lehman" (λ, `ProbabilisticRulesSolver`); range — отрезок параметра
+    pub fn solve(spec: &ProblemSpec, criterion: &str, range: (f64, f64)) -> Result<Sweep> {
+        ensure_complete(spec)?;
+        let (lo, hi) = range;
+        if lo.is_nan() || hi.is_nan() || lo >= hi {
+            anyhow::bail!("Диапазон параметра задан неверно: [{}, {}]", lo, hi);
+        }
+        let (param, evaluate): (&str, RuleSolver) = match criterion {
+            "hurwicz" | "hurwicz-regret" => ("α", DecisionRulesSolver::solve),
+            "ferstner" | "hodge-lehman" => ("λ", ProbabilisticRulesSolver::solve),
+            _ => anyhow::bail!("Parameter sweep is not defined for criterion: {}", criterion),
+        };
+        // сожаления и потери минимизируются
+        let minimize = criterion == "hurwicz-regret" || spec.orientation == Orientation::Loss;
+
+        let at_lo = evaluate(spec, criterion, Some(lo))?;
+        let at_hi = evaluate(spec, criterion, Some(hi))?;
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+        let score_of = |r: &DecisionResult, id: &str| r.scores.iter().find(|(a, _)| a == id).map_or(f64::NAN, |(_, v)| *v);
+        // прямые (значение в lo, наклон)
+        let lines: Vec<(f64, f64)> = ids.iter()
+            .map(|id| {
+                let (a, b) = (score_of(&at_lo, id), score_of(&at_hi, id));
+                (a, (b - a) / (hi - lo))
+            })
+            .collect();
+        let value = |i: usize, t: f64| lines[i].0 + (t - lo) * lines[i].1;
+        let regions = optimal_regions(&lines, range, minimize);
+
+        let names = |set: &[usize]| set.iter().map(|&i| ids[i].as_str()).collect::<Vec<_>>().join(", ");
+        let mut notes = Vec::new();
+        for w in regions.windows(2) {
+            let t = w[0].1;
+            notes.push(format!(
+                "Точка переключения {} = {:.4}: {} → {} (оценка {:.4})",
+                param, t, names(&w[0].2), names(&w[1].2), value(w[1].2[0], t)
+            ));
+        }
+        if regions.len() == 1 {
+            notes.push(format!("При всех {} из [{}, {}] оптимально: {}", param, lo, hi, names(&regions[0].2)));
+        }
+
+        // оценка — доля диапазона параметра, на которой альтернатива оптимальна
+        let mut scores: Vec<(String, f64)> = ids.iter().enumerate()
+            .map(|(i, id)| {
+                let width = regions.iter().filter(|r| r.2.contains(&i)).fold(0.0, |w, r| w + r.1 - r.0);
+                (id.clone(), width / (hi - lo))
+            })
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let best_share = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_, v)| (*v - best_share).abs() < EPS).map(|(id, _)| id.clone()).collect();
+
+        // узлы таблицы: равномерная сетка из 11 точек и точки переключения
+        let mut grid: Vec<f64> = (0..=10).map(|k| lo + (hi - lo) * k as f64 / 10.0).collect();
+        grid.extend(regions.iter().skip(1).map(|r| r.0));
+        grid.sort_by(|a, b| a.total_cmp(b));
+        grid.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+        let tables = vec![
+            ResultTable {
+                title: format!("Оценки — линейные функции {}: значения на концах отрезка и наклон k", param),
+                columns: vec![format!("s({})", lo), format!("s({})", hi), "k".to_string()],
+                rows: ids.iter().zip(lines.iter()).map(|(id, (a, k))| (id.clone(), vec![*a, a + k * (hi - lo), *k])).collect(),
+            },
+            ResultTable {
+                title: format!("Области оптимальности по {}", param),
+                columns: vec!["от".to_string(), "до".to_string()],
+                rows: regions.iter().map(|(a, b, set)| (names(set), vec![*a, *b])).collect(),
+            },
+            ResultTable {
+                title: format!("Оценки по критерию {} при {} =", criterion, param),
+                columns: grid.iter().map(|t| format!("{:.4}", t)).collect(),
+                rows: ids.iter().enumerate().map(|(i, id)| (id.clone(), grid.iter().map(|&t| value(i, t)).collect())).collect(),
+            },
+        ];
+
+        let breakpoints: Vec<f64> = regions.iter().skip(1).map(|r| r.0).collect();
+        let plot = render_plot(&ids, &lines, (lo, hi), &breakpoints, param);
+
+        Ok(Sweep {
+            result: DecisionResult {
+                chosen,
+                scores,
+                method: format!("sweep {} ({} ∈ [{}, {}], доля диапазона оптимальности)", criterion, param, lo, hi),
+                tables,
+                notes,
+                ..Default::default()
+            },
+            plot,
+        })
+    }
+}
+
+
+/// Области оптимальности для прямых s_i(t) = s_i(lo) + (t − lo)·k_i, заданных парами (s_i(lo), k_i):
+/// отрезки [от, до] с номерами лучших на них альтернатив (верхняя огибающая, при minimize — нижняя).
+/// Точки переключения ищутся среди попарных пересечений прямых, поэтому находятся точно.
+pub fn optimal_regions(lines: &[(f64, f64)], range: (f64, f64), minimize: bool) -> Vec<(f64, f64, Vec<usize>)> {
+    let (lo, hi) = range;
+    let sign = if minimize { -1.0 } else { 1.0 };
+    let value = |i: usize, t: f64| lines[i].0 + (t - lo) * lines[i].1;
+
+    // кандидаты в точки переключения — пересечения прямых внутри отрезка
+    let mut knots = vec![lo, hi];
+    for i in 0..lines.len() {
+        for j in i + 1..lines.len() {
+            let dk = lines[i].1 - lines[j].1;
+            if dk.abs() > EPS {
+                let t = lo + (lines[j].0 - lines[i].0) / dk;
+                if t > lo + EPS && t < hi - EPS {
+                    knots.push(t);
+                }
+            }
+        }
+    }
+    knots.sort_by(|a, b| a.total_cmp(b));
+    knots.dedup_by(|a, b| (*a - *b).abs() < EPS);
+
+    // на каждом отрезке между соседними узлами лидер не меняется — определяем его по середине
+    let best_at = |t: f64| -> Vec<usize> {
+        let top = (0..lines.len()).map(|i| sign * value(i, t)).fold(f64::NEG_INFINITY, f64::max);
+        (0..lines.len()).filter(|&i| sign * value(i, t) >= top - EPS * top.abs().max(1.0)).collect()
+    };
+    let mut regions: Vec<(f64, f64, Vec<usize>)> = Vec::new();
+    for w in knots.windows(2) {
+        let winners = best_at((w[0] + w[1]) / 2.0);
+        match regions.last_mut() {
+            Some(last) if last.2 == winners => last.1 = w[1],
+            _ => regions.push((w[0], w[1], winners)),
+        }
+    }
+    regions
+}
+
+
+/// Маркер альтернативы на графике: 1–9, затем буквы
+fn marker(i: usize) -> char {
+    const MARKERS: &[u8] = b"123456789abcdefghijklmnopqrstuvwxyz";
+    MARKERS.get(i).map_or('?', |&c| c as char)
+}
+
+
+/// Текстовый график прямых s_i(t); совпадающие точки — «*», точки переключения — «|»
+pub fn render_plot(ids: &[String], lines: &[(f64, f64)], range: (f64, f64), breakpoints: &[f64], param: &str) -> String {
+    let (lo, hi) = range;
+    let value = |i: usize, t: f64| lines[i].0 + (t - lo) * lines[i].1;
+    // прямые достигают экстремумов на концах отрезка
+    let ends: Vec<f64> = (0..lines.len()).flat_map(|i| [value(i, lo), value(i, hi)]).collect();
+    let y_max = ends.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let y_min = ends.iter().cloned().fold(f64::INFINITY, f64::min);
+    let span = (y_max - y_min).max(EPS);
+    let column = |t: f64| (((t - lo) / (hi - lo)) * (PLOT_WIDTH - 1) as f64).round() as usize;
+
+    let mut canvas = vec![vec![' '; PLOT_WIDTH]; PLOT_HEIGHT];
+    for &t in breakpoints {
+        let c = column(t);
+        canvas.iter_mut().for_each(|row| row[c] = '|');
+    }
+    let ts = (0..PLOT_WIDTH).map(|c| lo + (hi - lo) * c as f64 / (PLOT_WIDTH - 1) as f64);
+    for (c, t) in ts.enumerate() {
+        for i in 0..lines.len() {
+            let r = (((y_max - value(i, t)) / span) * (PLOT_HEIGHT - 1) as f64).round() as usize;
+            let cell = &mut canvas[r][c];
+            *cell = if *cell == ' ' || *cell == '|' { marker(i) } else { '*' };
+        }
+    }
+
+    let label_width = format!("{:.2}", y_max).len().max(format!("{:.2}", y_min).len());
+    let mut out = String::new();
+    for (r, row) in canvas.iter().enumerate() {
+        let label = match r {
+            0 => format!("{:.2}", y_max),
+            _ if r == PLOT_HEIGHT - 1 => format!("{:.2}", y_min),
+            _ => String::new(),
+        };
+        out.push_str(&format!("{:>w$} ┤{}\n", label, row.iter().collect::<String>(), w = label_width));
+    }
+    out.push_str(&format!("{:>w$} └{}\n", "", "─".repeat(PLOT_WIDTH), w = label_width));
+    let (left, right) = (format!("{}", lo), format!("{}", hi));
+    let gap = PLOT_WIDTH.saturating_sub(left.chars().count() + right.chars().count());
+    out.push_str(&format!("{:>w$}  {}{}{}  ({})\n", "", left, " ".repeat(gap), right, param, w = label_width));
+    for (i, id) in ids.iter().enumerate() {
+        out.push_str(&format!("  {} — {}\n", marker(i), id));
+    }
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hurwicz_breakpoint_is_exact() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "s1" }, { "id": "s2" }],
+            "alternatives": [
+                { "id": "A1", "values": [12, 0] },
+                { "id": "A2", "values": [6, 4] },
+                { "id": "A3", "values": [5, 5] }
+            ]
+        }"#).unwrap();
+        // s_A1 = 12α, s_A2 = 4 + 2α, s_A3 = 5: A3 лучше до α = 5/12, дальше A1; A2 не оптимальна нигде
+        let sweep = ParameterSweepSolver::solve(&spec, "hurwicz", (0.0, 1.0)).unwrap();
+        let regions = &sweep.result.tables[1].rows;
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].0, "A3");
+        assert_eq!(regions[1].0, "A1");
+        assert!((regions[0].1[1] - 5.0 / 12.0).abs() < 1e-12);
+        assert_eq!(sweep.result.chosen, vec!["A1"]);
+        let share = |id: &str| sweep.result.scores.iter().find(|(a, _)| a == id).unwrap().1;
+        assert!((share("A1") - 7.0 / 12.0).abs() < 1e-12);
+        assert!((share("A3") - 5.0 / 12.0).abs() < 1e-12);
+        assert_eq!(share("A2"), 0.0);
+        assert!(sweep.result.notes[0].starts_with("Точка переключения α = 0.4167: A3 → A1"));
+    }
+
+    #[test]
+    fn regions_of_lower_envelope_and_ties() {
+        // s_0 = t, s_1 = 1 − t: при минимизации 0 лучше до t = 0.5
+        let regions = optimal_regions(&[(0.0, 1.0), (1.0, -1.0)], (0.0, 1.0), true);
+        assert_eq!(regions, vec![(0.0, 0.5, vec![0]), (0.5, 1.0, vec![1])]);
+        // совпадающие прямые делят всю область
+        let regions = optimal_regions(&[(2.0, 1.0), (2.0, 1.0), (0.0, 1.0)], (0.0, 4.0), false);
+        assert_eq!(regions, vec![(0.0, 4.0, vec![0, 1])]);
+    }
+}
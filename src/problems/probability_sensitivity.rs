@@ -0,0 +1,334 @@
+// -----------------------------
+// src/problems/probability_sensitivity.rs
+// -----------------------------
+//! Чувствительность байесовского решения к вероятностям состояний.
+//!
+//! Ожидаемое значение M_i(p) = Σ_j p_j·a_ij линейно по p, поэтому:
+//! - при изменении одной вероятности p_j (остальные меняются пропорционально, сохраняя сумму 1)
+//!   M_i — прямые, и границы устойчивости решения находятся точно через `optimal_regions`;
+//!   для двух состояний это точки безразличия по p(S1), к ним выводится график;
+//! - область симплекса, где оптимальна альтернатива i, — многогранник (a_i − a_k)·p ≥ 0 для всех k;
+//!   для него находится «центр» с наибольшим запасом t (задача ЛП через `good_lp`);
+//!   доля объёма симплекса для двух состояний — точная длина отрезка по p(S1), иначе это лишь
+//!   оценка по равномерной выборке (в таблице так и отмечено);
+//! - минимальное по норме L1 изменение вероятностей, после которого другая альтернатива становится
+//!   не хуже выбранной, — задача ЛП с p = p⁰ + u − v, u, v ≥ 0, Σ(u + v) → min.
+//!
+//! Для матрицы потерь (`orientation` = loss) ожидаемые потери минимизируются.
+use crate::core::base::*;
+use crate::problems::parameter_sweep::{optimal_regions, render_plot};
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+use good_lp::{constraint, default_solver, variable, variables, Expression, ResolutionError, Solution, SolverModel};
+
+const EPS: f64 = 1e-9;
+// число случайных точек симплекса для оценки долей областей оптимальности (три и более состояния)
+const SAMPLES: usize = 20000;
+
+
+/// Результат анализа для печати и график для задачи с двумя состояниями
+pub struct Sensitivity {
+    pub result: DecisionResult,
+    pub plot: Option<String>,
+}
+
+
+pub struct ProbabilitySensitivitySolver;
+
+
+impl ProbabilitySensitivitySolver {
+    pub fn solve(spec: &ProblemSpec) -> Result<Sensitivity> {
+        ensure_complete(spec)?;
+        let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for sensitivity analysis"))?;
+        let n_states = probs.len();
+        if spec.alternatives.len() < 2 {
+            anyhow::bail!("Для анализа чувствительности нужны хотя бы две альтернативы");
+        }
+        if spec.alternatives.iter().any(|a| a.values.len() != n_states) {
+            anyhow::bail!("Alternative values length must equal number of states");
+        }
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+        let states = spec.state_names(n_states);
+        let minimize = spec.orientation == Orientation::Loss;
+        // в «выплатах» g = sign·a всё сводится к максимизации
+        let sign = spec.orientation.sign();
+        let gains: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.iter().map(|v| sign * v).collect()).collect();
+        let expected = |i: usize, p: &[f64]| -> f64 { spec.alternatives[i].values.iter().zip(p.iter()).map(|(v, q)| v * q).sum() };
+
+        let current = best(&gains, probs);
+        let mut scores: Vec<(String, f64)> = ids.iter().enumerate().map(|(i, id)| (id.clone(), expected(i, probs))).collect();
+        if minimize {
+            scores.sort_by(|a, b| a.1.total_cmp(&b.1));
+        } else {
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        }
+        let chosen: Vec<String> = current.iter().map(|&i| ids[i].clone()).collect();
+        let names = |set: &[usize]| set.iter().map(|&i| ids[i].as_str()).collect::<Vec<_>>().join(", ");
+        let mut notes = Vec::new();
+        let mut tables = Vec::new();
+        let mut plot = None;
+
+        // 1. Изменение одной вероятности: p_j = t, остальные — p⁰_k·(1 − t)/(1 − p⁰_j)
+        let mut one_at_a_time = Vec::new();
+        for j in 0..n_states {
+            if 1.0 - probs[j] < EPS {
+                notes.push(format!("{}: вероятность равна 1, остальные нельзя изменить пропорционально", states[j]));
+                continue;
+            }
+            let lines: Vec<(f64, f64)> = spec.alternatives.iter()
+                .map(|a| {
+                    let rest: f64 = (0..n_states).filter(|&k| k != j).map(|k| a.values[k] * probs[k]).sum::<f64>() / (1.0 - probs[j]);
+                    (rest, a.values[j] - rest)
+                })
+                .collect();
+            let regions = optimal_regions(&lines, (0.0, 1.0), minimize);
+            // отрезок вокруг p⁰_j, на котором текущий выбор остаётся оптимальным
+            let keep: Vec<&(f64, f64, Vec<usize>)> = regions.iter().filter(|r| current.iter().all(|i| r.2.contains(i))).collect();
+            let around = keep.iter().find(|r| r.0 <= probs[j] + EPS && probs[j] <= r.1 + EPS);
+            let (from, to) = around.map_or((probs[j], probs[j]), |r| (r.0, r.1));
+            one_at_a_time.push((states[j].clone(), vec![probs[j], from, to]));
+            for r in regions.windows(2) {
+                notes.push(format!("{}: при p = {:.4} оптимальность переходит от {} к {}", states[j], r[0].1, names(&r[0].2), names(&r[1].2)));
+            }
+            if n_states == 2 && j == 0 {
+                plot = Some(render_plot(&ids, &lines, (0.0, 1.0), &regions.iter().skip(1).map(|r| r.0).collect::<Vec<f64>>(), &format!("p({})", states[0])));
+                tables.push(ResultTable {
+                    title: format!("Области оптимальности по p({}) (точки безразличия — границы)", states[0]),
+                    columns: vec!["от".to_string(), "до".to_string()],
+                    rows: regions.iter().map(|(a, b, set)| (names(set), vec![*a, *b])).collect(),
+                });
+            }
+        }
+        tables.push(ResultTable {
+            title: "Изменение одной вероятности (остальные пропорционально): границы, в которых выбор не меняется".to_string(),
+            columns: vec!["p_j".to_string(), "от".to_string(), "до".to_string()],
+            rows: one_at_a_time,
+        });
+
+        // 2. Области оптимальности на симплексе: центр с наибольшим запасом и доля объёма
+        let shares = simplex_shares(&gains, n_states);
+        let mut regions = Vec::new();
+        for i in 0..ids.len() {
+            let (margin, center) = chebyshev_center(&gains, i)?;
+            let mut row = vec![shares[i], margin];
+            row.extend(center);
+            regions.push((ids[i].clone(), row));
+        }
+        let share = if n_states == 2 { "доля симплекса" } else { "доля симплекса (оценка)" };
+        let mut columns = vec![share.to_string(), "запас".to_string()];
+        columns.extend(states.iter().map(|s| format!("центр: {}", s)));
+        let estimate = if n_states == 2 {
+            String::new()
+        } else {
+            format!("; доля — оценка Монте-Карло по {} точкам", SAMPLES)
+        };
+        tables.push(ResultTable {
+            title: format!(
+                "Области оптимальности на симплексе вероятностей: центр с наибольшим запасом (запас ≤ 0 — области нет или она вырождена){}",
+                estimate
+            ),
+            columns,
+            rows: regions,
+        });
+
+        // 3. Минимальное изменение вероятностей (L1), после которого решение меняется
+        if current.len() > 1 {
+            notes.push(format!("Решение уже неоднозначно: {} равноценны при текущих вероятностях", names(&current)));
+        }
+        let leader = current[0];
+        let mut nearest: Vec<(usize, f64, Vec<f64>)> = Vec::new();
+        for k in (0..ids.len()).filter(|k| !current.contains(k)) {
+            if let Some((distance, p)) = nearest_switch(&gains, probs, leader, k)? {
+                nearest.push((k, distance, p));
+            }
+        }
+        nearest.sort_by(|a, b| a.1.total_cmp(&b.1));
+        match nearest.first() {
+            Some((k, distance, p)) => notes.push(format!(
+                "Минимальное изменение вероятностей, меняющее решение: L1 = {:.4} (в пользу {}, p = ({}))",
+                distance, ids[*k], p.iter().map(|x| format!("{:.4}", x)).collect::<Vec<_>>().join(", ")
+            )),
+            None => notes.push(format!("{} оптимальна при любых вероятностях состояний", names(&current))),
+        }
+        let mut columns = vec!["L1".to_string()];
+        columns.extend(states.iter().cloned());
+        tables.push(ResultTable {
+            title: format!("Ближайшие вероятности, при которых альтернатива не хуже {}", ids[leader]),
+            columns,
+            rows: nearest.into_iter().map(|(k, distance, p)| {
+                let mut row = vec![distance];
+                row.extend(p);
+                (ids[k].clone(), row)
+            }).collect(),
+        });
+
+        Ok(Sensitivity {
+            result: DecisionResult {
+                chosen,
+                scores,
+                method: "probability_sensitivity (bayes)".to_string(),
+                tables,
+                notes,
+                ..Default::default()
+            },
+            plot,
+        })
+    }
+}
+
+
+/// Номера альтернатив с наибольшим ожидаемым значением g при вероятностях p
+fn best(gains: &[Vec<f64>], p: &[f64]) -> Vec<usize> {
+    let values: Vec<f64> = gains.iter().map(|row| row.iter().zip(p.iter()).map(|(g, q)| g * q).sum()).collect();
+    let top = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    (0..values.len()).filter(|&i| values[i] >= top - EPS * top.abs().max(1.0)).collect()
+}
+
+
+/// Точка области оптимальности альтернативы i с наибольшим запасом t:
+/// max t при Σ_j (g_ij − g_kj)·p_j ≥ t для всех k ≠ i, p ∈ симплекс
+fn chebyshev_center(gains: &[Vec<f64>], i: usize) -> Result<(f64, Vec<f64>)> {
+    let n_states = gains[i].len();
+    let mut vars = variables!();
+    let p: Vec<_> = (0..n_states).map(|_| vars.add(variable().min(0.0))).collect();
+    let t = vars.add(variable());
+    let mut model = vars.maximise(t).using(default_solver);
+    let total: Expression = p.iter().sum();
+    model = model.with(constraint!(total == 1.0));
+    for other in gains.iter().enumerate().filter(|(k, _)| *k != i).map(|(_, row)| row) {
+        let margin: Expression = p.iter().enumerate().map(|(j, pj)| (gains[i][j] - other[j]) * *pj).sum();
+        model = model.with(constraint!(margin >= t));
+    }
+    let solution = model.solve()?;
+    Ok((solution.value(t), p.iter().map(|pj| solution.value(*pj).max(0.0)).collect()))
+}
+
+
+/// Ближайшие по L1 вероятности, при которых альтернатива k не хуже leader; None, если таких нет
+fn nearest_switch(gains: &[Vec<f64>], prior: &[f64], leader: usize, k: usize) -> Result<Option<(f64, Vec<f64>)>> {
+    let n_states = prior.len();
+    let mut vars = variables!();
+    let up: Vec<_> = (0..n_states).map(|_| vars.add(variable().min(0.0))).collect();
+    let down: Vec<_> = (0..n_states).map(|_| vars.add(variable().min(0.0))).collect();
+    let distance: Expression = up.iter().chain(down.iter()).sum();
+    let mut model = vars.minimise(distance).using(default_solver);
+    let shift: Expression = up.iter().zip(down.iter()).map(|(u, d)| *u - *d).sum();
+    model = model.with(constraint!(shift == 0.0));
+    for j in 0..n_states {
+        model = model.with(constraint!(up[j] - down[j] + prior[j] >= 0.0));
+    }
+    let advantage: Expression = (0..n_states).map(|j| (gains[k][j] - gains[leader][j]) * (up[j] - down[j] + prior[j])).sum();
+    model = model.with(constraint!(advantage >= 0.0));
+    match model.solve() {
+        Ok(solution) => {
+            let p: Vec<f64> = (0..n_states).map(|j| (prior[j] + solution.value(up[j]) - solution.value(down[j])).max(0.0)).collect();
+            let l1 = p.iter().zip(prior.iter()).map(|(a, b)| (a - b).abs()).sum();
+            Ok(Some((l1, p)))
+        }
+        Err(ResolutionError::Infeasible) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+
+/// Доли объёма симплекса, где оптимальна каждая альтернатива (при равенстве засчитываются всем лучшим).
+/// Для двух состояний симплекс — отрезок p(S1) ∈ [0, 1], и доли — точные длины областей оптимальности;
+/// иначе — оценка по равномерной выборке с фиксированным зерном
+fn simplex_shares(gains: &[Vec<f64>], n_states: usize) -> Vec<f64> {
+    if n_states == 2 {
+        let lines: Vec<(f64, f64)> = gains.iter().map(|g| (g[1], g[0] - g[1])).collect();
+        let regions = optimal_regions(&lines, (0.0, 1.0), false);
+        return (0..gains.len())
+            .map(|i| regions.iter().filter(|r| r.2.contains(&i)).map(|r| r.1 - r.0).sum())
+            .collect();
+    }
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut uniform = || {
+        // xorshift64*
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        ((state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    };
+    let mut counts = vec![0usize; gains.len()];
+    for _ in 0..SAMPLES {
+        // нормированные экспоненциальные величины равномерно распределены на симплексе
+        let e: Vec<f64> = (0..n_states).map(|_| -uniform().ln()).collect();
+        let total: f64 = e.iter().sum();
+        let p: Vec<f64> = e.iter().map(|x| x / total).collect();
+        best(gains, &p).into_iter().for_each(|i| counts[i] += 1);
+    }
+    counts.into_iter().map(|c| c as f64 / SAMPLES as f64).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(values: &[[f64; 3]], probs: &[f64]) -> ProblemSpec {
+        let n = probs.len();
+        let alternatives: Vec<String> = values.iter().enumerate()
+            .map(|(i, v)| format!(r#"{{ "id": "A{}", "values": {:?} }}"#, i + 1, &v[..n]))
+            .collect();
+        let criteria: Vec<String> = (1..=n).map(|j| format!(r#"{{ "id": "S{}" }}"#, j)).collect();
+        serde_json::from_str(&format!(
+            r#"{{ "criteria": [{}], "alternatives": [{}], "state_probabilities": {:?} }}"#,
+            criteria.join(", "), alternatives.join(", "), probs
+        )).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn two_state_breakeven() {
+        // 10t = 2t + 6(1 − t) при t = 3/7: до неё лучше A2, после — A1
+        let result = ProbabilitySensitivitySolver::solve(&spec(&[[10.0, 0.0, 0.0], [2.0, 6.0, 0.0]], &[0.7, 0.3])).unwrap().result;
+        assert_eq!(result.chosen, vec!["A1"]);
+        let regions = &result.tables[0].rows;
+        assert_eq!(regions[0].0, "A2");
+        assert!(close(regions[0].1[1], 3.0 / 7.0));
+        // по p(S2): 10(1 − t) = 2(1 − t) + 6t при t = 4/7
+        let bounds = &result.tables[1].rows;
+        assert!(close(bounds[0].1[1], 3.0 / 7.0) && close(bounds[0].1[2], 1.0));
+        assert!(close(bounds[1].1[1], 0.0) && close(bounds[1].1[2], 4.0 / 7.0));
+        // доли — точные длины областей; центры — вершины симплекса с запасами 8 и 6
+        let simplex = &result.tables[2];
+        assert_eq!(simplex.columns[0], "доля симплекса");
+        assert!(close(simplex.rows[0].1[0], 4.0 / 7.0) && close(simplex.rows[1].1[0], 3.0 / 7.0));
+        assert!(close(simplex.rows[0].1[1], 8.0) && close(simplex.rows[0].1[2], 1.0));
+        assert!(close(simplex.rows[1].1[1], 6.0) && close(simplex.rows[1].1[3], 1.0));
+        // до точки безразличия: 2·(0.7 − 3/7)
+        assert!(close(result.tables[3].rows[0].1[0], 2.0 * (0.7 - 3.0 / 7.0)));
+    }
+
+    #[test]
+    fn three_state_bounds_and_nearest_switch() {
+        let values = [[10.0, 0.0, 0.0], [0.0, 10.0, 0.0], [0.0, 0.0, 10.0]];
+        let result = ProbabilitySensitivitySolver::solve(&spec(&values, &[0.5, 0.3, 0.2])).unwrap().result;
+        assert_eq!(result.chosen, vec!["A1"]);
+
+        // p1 = t, p2 = 0.6(1 − t): 10t ≥ 6(1 − t) при t ≥ 3/8;
+        // p2 = t, p1 = 5/7·(1 − t): t ≤ 5/12; p3 = t, p1 = 0.625(1 − t): t ≤ 5/13
+        let bounds = &result.tables[0].rows;
+        for (row, (from, to)) in bounds.iter().zip([(0.375, 1.0), (0.0, 5.0 / 12.0), (0.0, 5.0 / 13.0)]) {
+            assert!(close(row.1[1], from) && close(row.1[2], to), "{}", row.0);
+        }
+
+        // симметричные области: доли оцениваются выборкой и отмечены как оценка
+        let simplex = &result.tables[1];
+        assert_eq!(simplex.columns[0], "доля симплекса (оценка)");
+        assert!(simplex.title.contains("Монте-Карло"));
+        assert!(simplex.rows.iter().all(|r| (r.1[0] - 1.0 / 3.0).abs() < 0.02));
+
+        // A2: перенести 0.1 из S1 в S2 → (0.4, 0.4, 0.2), L1 = 0.2; A3: 0.15 из S1 в S3, L1 = 0.3
+        let nearest = &result.tables[2].rows;
+        assert_eq!(nearest[0].0, "A2");
+        assert!(close(nearest[0].1[0], 0.2));
+        assert!(close(nearest[0].1[1], 0.4) && close(nearest[0].1[2], 0.4) && close(nearest[0].1[3], 0.2));
+        assert_eq!(nearest[1].0, "A3");
+        assert!(close(nearest[1].1[0], 0.3));
+    }
+}
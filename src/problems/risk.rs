@@ -1,45 +1,359 @@
 // -----------------------------
 // src/problems/risk.rs
 // -----------------------------
-//! Модуль с решателем задач, учитывающим риск через ожидаемое значение и критерий Вальда.
+//! Модуль с решателем задач, учитывающим риск через ожидаемое значение и профиль риска.
 //!
-//! Решатель `RiskSolver` оценивает альтернативы по ожиданию выплат с учётом вероятностей состояний,
-//! а также по минимальному выплачиваемому значению (критерий Вальда).
+//! Решатель `RiskSolver` оценивает альтернативы по ожиданию выплат с учётом вероятностей состояний
+//! и строит для каждой профиль риска (`RiskProfile`):
+//! - математическое ожидание, дисперсия, стандартное отклонение, коэффициент вариации;
+//! - полудисперсия (разброс ниже ожидания) и нижнее отклонение;
+//! - вероятность убытка (выплата < 0) и минимальная выплата (критерий Вальда);
+//! - VaR и CVaR на заданных уровнях доверия (в единицах убытка: положительное число — потеря);
+//! - принадлежность множеству, эффективному по критерию «среднее–дисперсия».
+//!
+//! `RiskSolver::information` оценивает ценность информации о состоянии:
+//! - EVPI = M[max_i a_ij] − max_i M[a_ij] — сколько стоит точное знание состояния;
+//! - EVSI для неточного прогноза с правдоподобием P(сигнал | состояние) (`ProblemSpec.likelihood`):
+//!   по каждому сигналу вероятности пересчитываются по формуле Байеса, выбирается лучшая
+//!   альтернатива, EVSI = Σ_k P(k)·max_i M[a_ij | k] − max_i M[a_ij]; эффективность = EVSI / EVPI.
 use crate::core::base::*;
+use crate::problems::bayes_update::posterior;
+use crate::problems::missing::ensure_complete;
 use anyhow::Result;
 
 
+/// Показатели риска одной альтернативы
+#[derive(Debug, Clone)]
+pub struct RiskProfile {
+    pub id: String,
+    pub expected: f64,
+    pub variance: f64,
+    pub std_dev: f64,
+    pub variation: f64,
+    pub semivariance: f64,
+    pub loss_probability: f64,
+    pub worst: f64,
+    // по уровням доверия в порядке `levels`
+    pub var: Vec<f64>,
+    pub cvar: Vec<f64>,
+    pub efficient: bool,
+}
+
+
 pub struct RiskSolver;
 
 
 impl RiskSolver {
-    pub fn solve(spec: &ProblemSpec) -> Result<DecisionResult> {
-        // Предположение: альтернативы содержат в values ожидаемые выплаты по состояниям (len == #states)
-        // state_probabilities должен быть указан и совпадать по длине с values
-        let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for risk method"))?;
-        let ns = probs.len();
-        if spec.alternatives.iter().any(|a| a.values.len() != ns) { anyhow::bail!("Alternative values length must equal number of states") }
+    /// levels — уровни доверия для VaR и CVaR, например [0.9, 0.95]
+    pub fn solve(spec: &ProblemSpec, levels: &[f64]) -> Result<DecisionResult> {
+        let profiles = risk_profiles(spec, levels)?;
 
+        // Основная оценка — ожидаемое значение; оно всегда достигается на эффективной альтернативе
+        let mut ev_scores: Vec<(String, f64)> = profiles.iter().map(|p| (p.id.clone(), p.expected)).collect();
+        ev_scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = ev_scores[0].1;
+        let chosen: Vec<String> = ev_scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
 
-        // Ожидаемое значение
-        let mut ev_scores: Vec<(String, f64)> = spec.alternatives.iter().map(|a|
-            (a.id.clone(), a.values.iter().zip(probs.iter()).map(|(v,p)| v * p).sum())
-        ).collect();
-        ev_scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+        let moments = ResultTable {
+            title: "Профиль риска".to_string(),
+            columns: ["M", "D", "σ", "CV", "полудисп.", "нижн. σ", "P(убыток)", "min (Вальд)"]
+                .iter().map(|c| c.to_string()).collect(),
+            rows: profiles.iter().map(|p| (p.id.clone(), vec![
+                p.expected, p.variance, p.std_dev, p.variation,
+                p.semivariance, p.semivariance.sqrt(), p.loss_probability, p.worst,
+            ])).collect(),
+        };
+        let tails = ResultTable {
+            title: "VaR и CVaR (убыток)".to_string(),
+            columns: levels.iter().map(|l| format!("VaR {}%", l * 100.0))
+                .chain(levels.iter().map(|l| format!("CVaR {}%", l * 100.0)))
+                .collect(),
+            rows: profiles.iter().map(|p| (p.id.clone(), p.var.iter().chain(p.cvar.iter()).cloned().collect())).collect(),
+        };
+        let efficient: Vec<&str> = profiles.iter().filter(|p| p.efficient).map(|p| p.id.as_str()).collect();
+        let notes = vec![format!("Эффективные по критерию «среднее–дисперсия»: {}", efficient.join(", "))];
 
-        // Критерий Вальда (максимин) по состояниям: для каждой альтернативы берем минимальное значение
-        let mut wal_scores: Vec<(String, f64)> = spec.alternatives.iter().map(|a|
-            (a.id.clone(), a.values.iter().cloned().fold(f64::INFINITY, f64::min))
-        ).collect();
-        wal_scores.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap());
+        Ok(DecisionResult{chosen, scores: ev_scores, method: "risk_ev".to_string(), tables: vec![moments, tails], notes, ..Default::default()})
+    }
+}
 
 
-        // Для наглядности вернём EV как основной
-        let best = ev_scores[0].1;
-        let chosen: Vec<String> = ev_scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+/// Ценность полной и выборочной информации
+#[derive(Debug, Clone)]
+pub struct InformationValue {
+    pub best_expected: f64,
+    pub expected_under_certainty: f64,
+    pub evpi: f64,
+    pub sample: Option<SampleInformation>,
+}
+
+
+/// Результат анализа неточного прогноза; индексы сигналов — как в `Likelihood.signals`
+#[derive(Debug, Clone)]
+pub struct SampleInformation {
+    pub signal_probabilities: Vec<f64>,
+    pub posteriors: Vec<Vec<f64>>,
+    // ожидания альтернатив при каждом сигнале: [сигнал][альтернатива]
+    pub conditional_expected: Vec<Vec<f64>>,
+    // оптимальное правило: номер альтернативы для каждого сигнала
+    pub rule: Vec<usize>,
+    pub expected_with_sample: f64,
+    pub evsi: f64,
+    pub efficiency: f64,
+}
+
+
+impl RiskSolver {
+    /// EVPI и (при заданном правдоподобии прогноза) EVSI с оптимальным правилом по сигналам
+    pub fn information(spec: &ProblemSpec) -> Result<DecisionResult> {
+        let info = information_value(spec)?;
+        let probs = spec.state_probabilities.as_ref().unwrap();
+
+        let mut scores: Vec<(String, f64)> = spec.alternatives.iter()
+            .map(|a| (a.id.clone(), a.values.iter().zip(probs.iter()).map(|(v,p)| v * p).sum()))
+            .collect();
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let best = scores[0].1;
+        let chosen: Vec<String> = scores.iter().filter(|(_,v)| (*v - best).abs() < 1e-9).map(|(id,_)| id.clone()).collect();
+
+        let mut summary = vec![
+            ("M лучшей альтернативы".to_string(), vec![info.best_expected]),
+            ("M при полной информации".to_string(), vec![info.expected_under_certainty]),
+            ("EVPI".to_string(), vec![info.evpi]),
+        ];
+        let mut tables = Vec::new();
+        let mut notes = Vec::new();
+        if let (Some(sample), Some(likelihood)) = (&info.sample, &spec.likelihood) {
+            summary.push(("M с прогнозом".to_string(), vec![sample.expected_with_sample]));
+            summary.push(("EVSI".to_string(), vec![sample.evsi]));
+            summary.push(("эффективность EVSI/EVPI".to_string(), vec![sample.efficiency]));
+
+            let mut columns = spec.state_names(probs.len());
+            columns.push("P(сигнал)".to_string());
+            tables.push(ResultTable {
+                title: "Апостериорные вероятности состояний".to_string(),
+                columns,
+                rows: likelihood.signals.iter().zip(sample.posteriors.iter().zip(sample.signal_probabilities.iter()))
+                    .map(|(signal, (post, p))| (signal.clone(), post.iter().cloned().chain(std::iter::once(*p)).collect()))
+                    .collect(),
+            });
+            tables.push(ResultTable {
+                title: "Ожидания альтернатив при сигнале".to_string(),
+                columns: spec.alternatives.iter().map(|a| a.id.clone()).collect(),
+                rows: likelihood.signals.iter().cloned().zip(sample.conditional_expected.iter().cloned()).collect(),
+            });
+            for (k, signal) in likelihood.signals.iter().enumerate() {
+                let i = sample.rule[k];
+                notes.push(format!(
+                    "Сигнал «{}»: выбрать {} (M = {:.4})",
+                    signal, spec.alternatives[i].id, sample.conditional_expected[k][i]
+                ));
+            }
+        }
+        tables.insert(0, ResultTable { title: "Ценность информации".to_string(), columns: vec!["значение".to_string()], rows: summary });
+
+        Ok(DecisionResult{chosen, scores, method: "risk_information".to_string(), tables, notes, ..Default::default()})
+    }
+}
+
+
+/// EVPI, а при наличии `spec.likelihood` — EVSI с оптимальным правилом по сигналам
+pub fn information_value(spec: &ProblemSpec) -> Result<InformationValue> {
+    let profiles = risk_profiles(spec, &[])?;
+    let probs = spec.state_probabilities.as_ref().unwrap();
+    let ns = probs.len();
+    let best_expected = profiles.iter().map(|p| p.expected).fold(f64::NEG_INFINITY, f64::max);
+    let column_max = |j: usize| spec.alternatives.iter().map(|a| a.values[j]).fold(f64::NEG_INFINITY, f64::max);
+    let expected_under_certainty: f64 = (0..ns).map(|j| probs[j] * column_max(j)).sum();
+    let evpi = expected_under_certainty - best_expected;
+
+    let sample = match &spec.likelihood {
+        None => None,
+        Some(likelihood) => {
+            likelihood.validate(ns)?;
 
+            let mut signal_probabilities = Vec::new();
+            let mut posteriors = Vec::new();
+            let mut conditional_expected = Vec::new();
+            let mut rule = Vec::new();
+            let mut expected_with_sample = 0.0;
+            for row in &likelihood.matrix {
+                // P(k) = Σ_j P(k | j)·p_j;  P(j | k) = P(k | j)·p_j / P(k)
+                let p_signal: f64 = row.iter().zip(probs.iter()).map(|(l, p)| l * p).sum();
+                let posterior = posterior(probs, row).unwrap_or_else(|| probs.clone());
+                let expected: Vec<f64> = spec.alternatives.iter()
+                    .map(|a| a.values.iter().zip(posterior.iter()).map(|(v, p)| v * p).sum())
+                    .collect();
+                let best = (0..expected.len()).fold(0, |b, i| if expected[i] > expected[b] + 1e-12 { i } else { b });
+                expected_with_sample += p_signal * expected[best];
+                signal_probabilities.push(p_signal);
+                posteriors.push(posterior);
+                conditional_expected.push(expected);
+                rule.push(best);
+            }
+            let evsi = expected_with_sample - best_expected;
+            Some(SampleInformation {
+                signal_probabilities,
+                posteriors,
+                conditional_expected,
+                rule,
+                expected_with_sample,
+                evsi,
+                efficiency: if evpi > 1e-12 { evsi / evpi } else { 0.0 },
+            })
+        }
+    };
+
+    Ok(InformationValue { best_expected, expected_under_certainty, evpi, sample })
+}
+
+
+/// Профили риска всех альтернатив задачи
+pub fn risk_profiles(spec: &ProblemSpec, levels: &[f64]) -> Result<Vec<RiskProfile>> {
+    ensure_complete(spec)?;
+    // Предположение: альтернативы содержат в values выплаты по состояниям (len == #states)
+    // state_probabilities должен быть указан и совпадать по длине с values
+    let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for risk method"))?;
+    let ns = probs.len();
+    if spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+    if spec.alternatives.iter().any(|a| a.values.len() != ns) { anyhow::bail!("Alternative values length must equal number of states") }
+    if probs.iter().any(|p| *p < 0.0) || (probs.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+        anyhow::bail!("Вероятности состояний должны быть неотрицательными и давать в сумме 1");
+    }
+    if let Some(l) = levels.iter().find(|l| **l <= 0.0 || **l >= 1.0) {
+        anyhow::bail!("Уровень доверия должен лежать в (0, 1): {}", l);
+    }
+
+    let mut profiles: Vec<RiskProfile> = spec.alternatives.iter().map(|a| {
+        let x = &a.values;
+        let expected: f64 = x.iter().zip(probs.iter()).map(|(v,p)| v * p).sum();
+        let variance: f64 = x.iter().zip(probs.iter()).map(|(v,p)| p * (v - expected).powi(2)).sum();
+        let semivariance: f64 = x.iter().zip(probs.iter()).filter(|(v,_)| **v < expected).map(|(v,p)| p * (v - expected).powi(2)).sum();
+        let std_dev = variance.sqrt();
+        RiskProfile {
+            id: a.id.clone(),
+            expected,
+            variance,
+            std_dev,
+            variation: if expected.abs() > 1e-12 { std_dev / expected.abs() } else { f64::INFINITY },
+            semivariance,
+            loss_probability: x.iter().zip(probs.iter()).filter(|(v,_)| **v < 0.0).map(|(_,p)| p).sum(),
+            worst: x.iter().zip(probs.iter()).filter(|(_,p)| **p > 0.0).map(|(v,_)| *v).fold(f64::INFINITY, f64::min),
+            var: levels.iter().map(|&l| value_at_risk(x, probs, l)).collect(),
+            cvar: levels.iter().map(|&l| conditional_value_at_risk(x, probs, l)).collect(),
+            efficient: true,
+        }
+    }).collect();
+
+    // Эффективность по Марковицу: нет альтернативы с не меньшим ожиданием и не большей дисперсией (хотя бы одно строго)
+    let dominated: Vec<bool> = profiles.iter().map(|a| profiles.iter().any(|b| {
+        b.expected >= a.expected - 1e-9 && b.variance <= a.variance + 1e-9
+            && (b.expected > a.expected + 1e-9 || b.variance < a.variance - 1e-9)
+    })).collect();
+    for (p, d) in profiles.iter_mut().zip(dominated) {
+        p.efficient = !d;
+    }
+    Ok(profiles)
+}
+
+
+/// Выплаты по возрастанию вместе с вероятностями
+fn sorted_outcomes(values: &[f64], probs: &[f64]) -> Vec<(f64, f64)> {
+    let mut outcomes: Vec<(f64, f64)> = values.iter().cloned().zip(probs.iter().cloned()).filter(|(_,p)| *p > 0.0).collect();
+    outcomes.sort_by(|a,b| a.0.total_cmp(&b.0));
+    outcomes
+}
+
+
+/// VaR_α = −q_{1−α}: нижний квантиль выплаты уровня 1 − α, взятый со знаком минус
+fn value_at_risk(values: &[f64], probs: &[f64], level: f64) -> f64 {
+    let tail = 1.0 - level;
+    let outcomes = sorted_outcomes(values, probs);
+    let mut cumulative = 0.0;
+    for (v, p) in &outcomes {
+        cumulative += p;
+        if cumulative >= tail - 1e-12 {
+            return -v;
+        }
+    }
+    -outcomes[outcomes.len() - 1].0
+}
+
+
+/// CVaR_α — средний убыток в худших (1 − α) случаях; атом на границе хвоста берётся частично
+fn conditional_value_at_risk(values: &[f64], probs: &[f64], level: f64) -> f64 {
+    let tail = 1.0 - level;
+    let mut remaining = tail;
+    let mut total = 0.0;
+    for (v, p) in sorted_outcomes(values, probs) {
+        let share = p.min(remaining);
+        total += share * v;
+        remaining -= share;
+        if remaining <= 1e-12 {
+            break;
+        }
+    }
+    -total / tail
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn var_and_cvar_on_discrete_distribution() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "s1" }, { "id": "s2" }, { "id": "s3" }, { "id": "s4" }],
+            "alternatives": [{ "id": "A", "values": [-100, 0, 50, 200] }],
+            "state_probabilities": [0.1, 0.2, 0.3, 0.4]
+        }"#).unwrap();
+        let profile = &risk_profiles(&spec, &[0.9, 0.75, 0.5]).unwrap()[0];
+        assert!((profile.expected - 85.0).abs() < 1e-9);
+        assert!((profile.loss_probability - 0.1).abs() < 1e-12);
+        assert_eq!(profile.worst, -100.0);
+        // хвост 0.1 — ровно атом −100; хвост 0.25 — атом −100 и 0.15 от нуля; хвост 0.5 — ещё 0.2 от 50
+        let expected_var = [100.0, 0.0, -50.0];
+        let expected_cvar = [100.0, 10.0 / 0.25, 0.0];
+        for k in 0..3 {
+            assert!((profile.var[k] - expected_var[k]).abs() < 1e-9, "VaR {}", k);
+            assert!((profile.cvar[k] - expected_cvar[k]).abs() < 1e-9, "CVaR {}", k);
+        }
+    }
+    #[test]
+    fn evpi_on_newsvendor_data() {
+        let spec = crate::core::parser::read_spec("data/data_for_risk_newsvendor.json").unwrap();
+        let p = spec.state_probabilities.clone().unwrap();
+        let info = information_value(&spec).unwrap();
+        // лучшая по ожиданию — «При 200», максимумы по столбцам — 380, 790, 1200, 1450, 1700
+        let dot = |x: [f64; 5]| x.iter().zip(p.iter()).map(|(v, q)| v * q).sum::<f64>();
+        let best = dot([-100.0, 550.0, 1200.0, 1050.0, 900.0]);
+        let certain = dot([380.0, 790.0, 1200.0, 1450.0, 1700.0]);
+        assert!((info.best_expected - best).abs() < 1e-9);
+        assert!((info.expected_under_certainty - certain).abs() < 1e-9);
+        assert!((info.evpi - (certain - best)).abs() < 1e-9);
+        assert!(info.sample.is_none());
+    }
 
-        // Объединим в один вектор результатов (здесь только EV для простоты)
-        Ok(DecisionResult{chosen, scores: ev_scores, method: "risk_ev".to_string()})
+    #[test]
+    fn evsi_for_imperfect_forecast() {
+        let spec: ProblemSpec = serde_json::from_str(r#"{
+            "criteria": [{ "id": "s1" }, { "id": "s2" }],
+            "alternatives": [
+                { "id": "A", "values": [100, -100] },
+                { "id": "B", "values": [0, 0] }
+            ],
+            "likelihood": { "signals": ["хорошо", "плохо"], "matrix": [[0.8, 0.2], [0.2, 0.8]] },
+            "state_probabilities": [0.5, 0.5]
+        }"#).unwrap();
+        let info = information_value(&spec).unwrap();
+        assert!((info.evpi - 50.0).abs() < 1e-9);
+        // «хорошо»: апостериорные (0.8, 0.2), M[A] = 60 → A; «плохо»: M[A] = −60 → B
+        let sample = info.sample.unwrap();
+        assert_eq!(sample.rule, vec![0, 1]);
+        assert!((sample.posteriors[0][0] - 0.8).abs() < 1e-12);
+        assert!((sample.expected_with_sample - 30.0).abs() < 1e-9);
+        assert!((sample.evsi - 30.0).abs() < 1e-9);
+        assert!((sample.efficiency - 0.6).abs() < 1e-9);
     }
 }
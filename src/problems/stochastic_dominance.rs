@@ -0,0 +1,226 @@
+// -----------------------------
+// src/problems/stochastic_dominance.rs
+// -----------------------------
+//! Стохастическое доминирование между рискованными альтернативами.
+//!
+//! Каждая альтернатива задаёт дискретное распределение выплат (`values` с вероятностями
+//! `state_probabilities`). Для функций распределения F_A и F_B:
+//! - первого порядка (FSD): F_A(x) ≤ F_B(x) при всех x — A лучше для любой возрастающей полезности;
+//! - второго порядка (SSD): ∫F_A ≤ ∫F_B — для любой возрастающей вогнутой (неприятие риска);
+//! - третьего порядка (TSD): ∫∫F_A ≤ ∫∫F_B и M[A] ≥ M[B] — дополнительно при склонности к «правой асимметрии».
+//!
+//! Доминирование строгое: распределения должны различаться. Альтернативы, не доминируемые
+//! в выбранном порядке, образуют эффективное множество; `prefilter` оставляет в задаче только его
+//! (например, перед критериями `ProbabilisticRulesSolver`).
+//! Если значения — потери (`orientation` = loss), сравниваются распределения выплат −a.
+use crate::core::base::*;
+use crate::problems::missing::ensure_complete;
+use anyhow::Result;
+
+const EPS: f64 = 1e-9;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DominanceOrder {
+    First,
+    Second,
+    Third,
+}
+
+
+impl DominanceOrder {
+    /// "fsd" | "ssd" | "tsd"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "fsd" => Ok(DominanceOrder::First),
+            "ssd" => Ok(DominanceOrder::Second),
+            "tsd" => Ok(DominanceOrder::Third),
+            _ => anyhow::bail!("Unknown stochastic dominance order: {}", name),
+        }
+    }
+
+    fn degree(&self) -> usize {
+        match self {
+            DominanceOrder::First => 1,
+            DominanceOrder::Second => 2,
+            DominanceOrder::Third => 3,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            DominanceOrder::First => "FSD",
+            DominanceOrder::Second => "SSD",
+            DominanceOrder::Third => "TSD",
+        }
+    }
+}
+
+
+pub struct StochasticDominanceSolver;
+
+
+impl StochasticDominanceSolver {
+    pub fn solve(spec: &ProblemSpec, order: DominanceOrder) -> Result<DecisionResult> {
+        let matrix = dominance_matrix(spec)?;
+        let ids: Vec<String> = spec.alternatives.iter().map(|a| a.id.clone()).collect();
+        let k = order.degree();
+
+        // оценка — число альтернатив, доминируемых в выбранном порядке
+        let mut scores: Vec<(String, f64)> = ids.iter().zip(matrix.iter())
+            .map(|(id, row)| (id.clone(), row.iter().filter(|d| matches!(d, Some(x) if *x <= k)).count() as f64))
+            .collect();
+        scores.sort_by(|a,b| b.1.total_cmp(&a.1));
+        let chosen: Vec<String> = efficient(&matrix, k).into_iter().map(|i| ids[i].clone()).collect();
+
+        let notes = [DominanceOrder::First, DominanceOrder::Second, DominanceOrder::Third].iter()
+            .map(|o| {
+                let set: Vec<&str> = efficient(&matrix, o.degree()).into_iter().map(|i| ids[i].as_str()).collect();
+                format!("Эффективные по {}: {}", o.name(), set.join(", "))
+            })
+            .collect();
+        let table = ResultTable {
+            title: "Доминирование строки над столбцом (1 — FSD, 2 — SSD, 3 — TSD, 0 — нет)".to_string(),
+            columns: ids.clone(),
+            rows: ids.iter().cloned()
+                .zip(matrix.iter().map(|row| row.iter().map(|d| d.unwrap_or(0) as f64).collect()))
+                .collect(),
+        };
+
+        Ok(DecisionResult {
+            chosen,
+            scores,
+            method: format!("stochastic_dominance ({})", order.name()),
+            tables: vec![table],
+            notes,
+            ..Default::default()
+        })
+    }
+}
+
+
+/// Оставляет в задаче только альтернативы, эффективные в заданном порядке, и отмечает исключённые в `notes`
+pub fn prefilter(spec: &mut ProblemSpec, order: DominanceOrder) -> Result<()> {
+    let matrix = dominance_matrix(spec)?;
+    let keep = efficient(&matrix, order.degree());
+    let dropped: Vec<String> = (0..spec.alternatives.len())
+        .filter(|i| !keep.contains(i))
+        .map(|i| spec.alternatives[i].id.clone())
+        .collect();
+    if !dropped.is_empty() {
+        spec.notes.push(format!("Исключены как стохастически доминируемые ({}): {}", order.name(), dropped.join(", ")));
+        spec.alternatives = spec.alternatives.drain(..).enumerate()
+            .filter(|(i, _)| keep.contains(i))
+            .map(|(_, alt)| alt)
+            .collect();
+    }
+    Ok(())
+}
+
+
+/// matrix[a][b] — наименьший порядок (1..=3), в котором a доминирует b, или None
+pub fn dominance_matrix(spec: &ProblemSpec) -> Result<Vec<Vec<Option<usize>>>> {
+    ensure_complete(spec)?;
+    let probs = spec.state_probabilities.as_ref().ok_or_else(|| anyhow::anyhow!("Probabilities required for stochastic dominance"))?;
+    if spec.alternatives.is_empty() { anyhow::bail!("Empty problem") }
+    if spec.alternatives.iter().any(|a| a.values.len() != probs.len()) {
+        anyhow::bail!("Alternative values length must equal number of states");
+    }
+    let sign = spec.orientation.sign();
+    let payoffs: Vec<Vec<f64>> = spec.alternatives.iter().map(|a| a.values.iter().map(|v| sign * v).collect()).collect();
+    Ok(payoffs.iter()
+        .map(|a| payoffs.iter().map(|b| dominance_degree(a, b, probs)).collect())
+        .collect())
+}
+
+
+/// Номера альтернатив, не доминируемых ни одной другой в порядке ≤ degree
+fn efficient(matrix: &[Vec<Option<usize>>], degree: usize) -> Vec<usize> {
+    (0..matrix.len())
+        .filter(|&j| !matrix.iter().any(|row| matches!(row[j], Some(d) if d <= degree)))
+        .collect()
+}
+
+
+/// Сравнение распределений в узлах общей сетки x_0 < x_1 < … (все значения обеих альтернатив).
+/// D1 = F_A − F_B постоянна на [x_k, x_{k+1}), D2 = ∫D1 кусочно-линейна, D3 = ∫D2 кусочно-квадратична.
+fn dominance_degree(a: &[f64], b: &[f64], probs: &[f64]) -> Option<usize> {
+    let cdf = |values: &[f64], x: f64| -> f64 {
+        values.iter().zip(probs.iter()).filter(|(v, _)| **v <= x).map(|(_, p)| p).sum()
+    };
+    let mut knots: Vec<f64> = a.iter().chain(b.iter()).cloned().collect();
+    knots.sort_by(|x, y| x.total_cmp(y));
+    knots.dedup_by(|x, y| (*x - *y).abs() < EPS);
+
+    let d1: Vec<f64> = knots.iter().map(|&x| cdf(a, x) - cdf(b, x)).collect();
+    if d1.iter().all(|d| d.abs() < EPS) {
+        return None; // одинаковые распределения
+    }
+    if d1.iter().all(|d| *d <= EPS) {
+        return Some(1);
+    }
+
+    // значения D2 и D3 в узлах (левее первого узла обе функции распределения равны нулю)
+    let mut d2 = vec![0.0; knots.len()];
+    let mut d3 = vec![0.0; knots.len()];
+    // наибольшее значение D3 на каждом отрезке: в концах либо в нуле D2 внутри отрезка
+    let mut d3_max: f64 = 0.0;
+    for k in 0..knots.len() - 1 {
+        let h = knots[k + 1] - knots[k];
+        d2[k + 1] = d2[k] + d1[k] * h;
+        d3[k + 1] = d3[k] + d2[k] * h + d1[k] * h * h / 2.0;
+        if d2[k] > 0.0 && d2[k + 1] < 0.0 {
+            let t = d2[k] / -d1[k];
+            d3_max = d3_max.max(d3[k] + d2[k] * t / 2.0);
+        }
+        d3_max = d3_max.max(d3[k + 1]);
+    }
+    // правее последнего узла D1 = 0, D2 = M[B] − M[A] постоянна;
+    // допуски масштабируются: D2 растёт как размах выплат, D3 — как его квадрат
+    let span = (knots[knots.len() - 1] - knots[0]).max(1.0);
+    if d2.iter().all(|d| *d <= EPS * span) {
+        return Some(2);
+    }
+    if d3_max <= EPS * span * span && d2[knots.len() - 1] <= EPS * span {
+        return Some(3);
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_order_dominance() {
+        let p = [0.5, 0.5];
+        assert_eq!(dominance_degree(&[2.0, 3.0], &[1.0, 3.0], &p), Some(1));
+        assert_eq!(dominance_degree(&[1.0, 3.0], &[2.0, 3.0], &p), None);
+    }
+
+    #[test]
+    fn mean_preserving_spread_is_second_order_only() {
+        // B = {1, 3} — разброс вокруг гарантированных 2: F_A − F_B = (−0.5, 0.5, 0) меняет знак, ∫ ≤ 0
+        let p = [0.5, 0.5];
+        assert_eq!(dominance_degree(&[2.0, 2.0], &[1.0, 3.0], &p), Some(2));
+        assert_eq!(dominance_degree(&[1.0, 3.0], &[2.0, 2.0], &p), None);
+    }
+
+    #[test]
+    fn skewness_gives_third_order_only() {
+        // равные средние 1.5: D2 = (0, −0.25, 0.25, 0) меняет знак, D3 = (0, −0.125, −0.125, 0) ≤ 0
+        let p = [0.25; 4];
+        let (a, b) = ([1.0, 1.0, 1.0, 3.0], [0.0, 2.0, 2.0, 2.0]);
+        assert_eq!(dominance_degree(&a, &b, &p), Some(3));
+        assert_eq!(dominance_degree(&b, &a, &p), None);
+    }
+
+    #[test]
+    fn identical_distributions_do_not_dominate() {
+        // одинаковое распределение при разной раскладке по состояниям
+        let p = [0.5, 0.5];
+        assert_eq!(dominance_degree(&[1.0, 3.0], &[3.0, 1.0], &p), None);
+        assert_eq!(dominance_degree(&[1.0, 3.0], &[1.0, 3.0], &p), None);
+    }
+}
@@ -0,0 +1,276 @@
+// -----------------------------
+// src/problems/weighting.rs
+// -----------------------------
+//! Объективные и ранговые методы определения весов критериев.
+//!
+//! Когда экспертных весов нет, `WeightedSumSolver` считает все `Criterion.weight` равными 1.0.
+//! Здесь веса вычисляются по самой матрице решений:
+//! - энтропия Шеннона (чем сильнее различаются значения критерия, тем он важнее);
+//! - CRITIC (контрастность × несогласованность с остальными критериями);
+//! - стандартное отклонение нормированных значений;
+//! - средние (равные) веса.
+//!
+//! А также по порядку важности, заданному пользователем:
+//! - центроид рангов (ROC) и сумма рангов (RS).
+//!
+//! Полученные веса можно объединить с субъективными (заданными в `Criterion.weight`).
+use crate::core::base::ProblemSpec;
+use anyhow::Result;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightingMethod {
+    Entropy,
+    Critic,
+    StdDev,
+    Mean,
+    RankOrderCentroid,
+    RankSum,
+}
+
+
+impl WeightingMethod {
+    /// "entropy" | "critic" | "stddev" | "mean" | "roc" | "rank-sum"
+    pub fn from_name(name: &str) -> Result<Self> {
+        Ok(match name {
+            "entropy" => WeightingMethod::Entropy,
+            "critic" => WeightingMethod::Critic,
+            "stddev" => WeightingMethod::StdDev,
+            "mean" => WeightingMethod::Mean,
+            "roc" => WeightingMethod::RankOrderCentroid,
+            "rank-sum" => WeightingMethod::RankSum,
+            _ => anyhow::bail!("Unknown weighting method: {}", name),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeightingMethod::Entropy => "entropy",
+            WeightingMethod::Critic => "critic",
+            WeightingMethod::StdDev => "stddev",
+            WeightingMethod::Mean => "mean",
+            WeightingMethod::RankOrderCentroid => "roc",
+            WeightingMethod::RankSum => "rank-sum",
+        }
+    }
+}
+
+
+/// Веса критериев задачи выбранным методом.
+/// `order` — id критериев от самого важного к наименее важному (нужен только для ROC и RS).
+pub fn objective_weights(spec: &ProblemSpec, method: WeightingMethod, order: Option<&[String]>) -> Result<Vec<(String, f64)>> {
+    let n = spec.criteria.len();
+    if n == 0 || spec.alternatives.is_empty() {
+        anyhow::bail!("Empty problem")
+    }
+    if spec.alternatives.iter().any(|a| a.values.len() != n) {
+        anyhow::bail!("Alternative values length mismatch")
+    }
+
+    let raw: Vec<f64> = match method {
+        WeightingMethod::Entropy => entropy(spec),
+        WeightingMethod::Critic => critic(spec),
+        WeightingMethod::StdDev => normalized_columns(spec).iter().map(|c| std_dev(c)).collect(),
+        WeightingMethod::Mean => vec![1.0; n],
+        WeightingMethod::RankOrderCentroid | WeightingMethod::RankSum => {
+            let order = order.ok_or_else(|| anyhow::anyhow!("Criterion order required for rank-based weights"))?;
+            rank_weights(spec, method, order)?
+        }
+    };
+
+    let total: f64 = raw.iter().sum();
+    if total <= 0.0 {
+        anyhow::bail!("Все критерии имеют одинаковые значения: метод {} не различает их", method.name());
+    }
+    Ok(spec.criteria.iter().zip(raw).map(|(c, w)| (c.id.clone(), w / total)).collect())
+}
+
+
+/// Объединение субъективных весов с объективными.
+/// alpha = None — мультипликативно: w = s·o / Σ s·o;
+/// alpha = Some(a) — линейно: w = a·s + (1 − a)·o (s предварительно нормируются).
+pub fn combine_weights(subjective: &[(String, f64)], objective: &[(String, f64)], alpha: Option<f64>) -> Vec<(String, f64)> {
+    let s_total: f64 = subjective.iter().map(|(_, w)| w).sum();
+    let combined: Vec<(String, f64)> = subjective
+        .iter()
+        .zip(objective.iter())
+        .map(|((id, s), (_, o))| {
+            let w = match alpha {
+                Some(a) => a * s / s_total + (1.0 - a) * o,
+                None => s * o,
+            };
+            (id.clone(), w)
+        })
+        .collect();
+    let total: f64 = combined.iter().map(|(_, w)| w).sum();
+    combined.into_iter().map(|(id, w)| (id, w / total)).collect()
+}
+
+
+/// Столбцы матрицы, нормированные min-max с учётом направления критерия (1 — лучшее значение)
+pub fn normalized_columns(spec: &ProblemSpec) -> Vec<Vec<f64>> {
+    spec.criteria
+        .iter()
+        .enumerate()
+        .map(|(j, crit)| {
+            let col: Vec<f64> = spec.alternatives.iter().map(|a| a.values[j]).collect();
+            let min = col.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = col.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let range = max - min;
+            col.iter()
+                .map(|v| {
+                    if range == 0.0 {
+                        0.0
+                    } else if crit.maximize.unwrap_or(true) {
+                        (v - min) / range
+                    } else {
+                        (max - v) / range
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+
+fn mean(xs: &[f64]) -> f64 {
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+
+fn std_dev(xs: &[f64]) -> f64 {
+    let m = mean(xs);
+    (xs.iter().map(|x| (x - m).powi(2)).sum::<f64>() / xs.len() as f64).sqrt()
+}
+
+
+/// Энтропийный метод: d_j = 1 − E_j, где E_j = −Σ p_ij·ln p_ij / ln m
+fn entropy(spec: &ProblemSpec) -> Vec<f64> {
+    let m = spec.alternatives.len();
+    (0..spec.criteria.len())
+        .map(|j| {
+            let col: Vec<f64> = spec.alternatives.iter().map(|a| a.values[j]).collect();
+            // доли p_ij определены для неотрицательных значений, поэтому столбец при необходимости сдвигается
+            let min = col.iter().cloned().fold(f64::INFINITY, f64::min);
+            let shift = if min < 0.0 { -min } else { 0.0 };
+            let total: f64 = col.iter().map(|v| v + shift).sum();
+            if total == 0.0 || m < 2 {
+                return 0.0;
+            }
+            let e: f64 = col
+                .iter()
+                .map(|v| (v + shift) / total)
+                .filter(|&p| p > 0.0)
+                .map(|p| p * p.ln())
+                .sum::<f64>()
+                / -(m as f64).ln();
+            1.0 - e
+        })
+        .collect()
+}
+
+
+/// CRITIC: C_j = σ_j · Σ_k (1 − r_jk), r_jk — корреляция нормированных столбцов
+fn critic(spec: &ProblemSpec) -> Vec<f64> {
+    let cols = normalized_columns(spec);
+    let sigma: Vec<f64> = cols.iter().map(|c| std_dev(c)).collect();
+    let correlation = |a: usize, b: usize| {
+        if sigma[a] == 0.0 || sigma[b] == 0.0 {
+            return 0.0;
+        }
+        let (ma, mb) = (mean(&cols[a]), mean(&cols[b]));
+        let cov = cols[a].iter().zip(cols[b].iter()).map(|(x, y)| (x - ma) * (y - mb)).sum::<f64>() / cols[a].len() as f64;
+        cov / (sigma[a] * sigma[b])
+    };
+    (0..cols.len())
+        .map(|j| sigma[j] * (0..cols.len()).map(|k| 1.0 - correlation(j, k)).sum::<f64>())
+        .collect()
+}
+
+
+/// ROC: w_k = (1/n)·Σ_{i=k..n} 1/i; RS: w_k = 2(n + 1 − k) / (n(n + 1)); k — ранг критерия
+fn rank_weights(spec: &ProblemSpec, method: WeightingMethod, order: &[String]) -> Result<Vec<f64>> {
+    let n = spec.criteria.len();
+    if order.len() != n {
+        anyhow::bail!("Порядок должен содержать все {} критериев", n);
+    }
+    spec.criteria
+        .iter()
+        .map(|c| {
+            let k = order.iter().position(|id| id == &c.id)
+                .ok_or_else(|| anyhow::anyhow!("Критерий {} отсутствует в порядке важности", c.id))? + 1;
+            Ok(match method {
+                WeightingMethod::RankOrderCentroid => (k..=n).map(|i| 1.0 / i as f64).sum::<f64>() / n as f64,
+                _ => 2.0 * (n + 1 - k) as f64 / (n * (n + 1)) as f64,
+            })
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(criteria: &str, rows: &[&[f64]]) -> ProblemSpec {
+        let alternatives: Vec<String> = rows.iter().enumerate()
+            .map(|(i, r)| format!(r#"{{"id": "A{}", "values": {:?}}}"#, i + 1, r))
+            .collect();
+        serde_json::from_str(&format!(r#"{{"criteria": {}, "alternatives": [{}]}}"#, criteria, alternatives.join(", "))).unwrap()
+    }
+
+    fn values(weights: &[(String, f64)]) -> Vec<f64> {
+        weights.iter().map(|(_, w)| *w).collect()
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn rank_based_weights() {
+        let spec = spec(r#"[{"id": "a"}, {"id": "b"}, {"id": "c"}]"#, &[&[1.0, 2.0, 3.0]]);
+        let order: Vec<String> = ["b", "a", "c"].iter().map(|s| s.to_string()).collect();
+        // ROC при n = 3: (11/18, 5/18, 2/18) по рангам; b — первый, a — второй
+        let roc = objective_weights(&spec, WeightingMethod::RankOrderCentroid, Some(&order)).unwrap();
+        assert_close(&values(&roc), &[5.0 / 18.0, 11.0 / 18.0, 2.0 / 18.0]);
+        // RS: (3, 2, 1)/6
+        let rs = objective_weights(&spec, WeightingMethod::RankSum, Some(&order)).unwrap();
+        assert_close(&values(&rs), &[2.0 / 6.0, 3.0 / 6.0, 1.0 / 6.0]);
+        assert!(objective_weights(&spec, WeightingMethod::RankSum, None).is_err());
+    }
+
+    #[test]
+    fn entropy_weights() {
+        // столбцы: (1, 1) — E = 1; (1, 3) — p = (1/4, 3/4), E = 0.811278; (0, 2) — E = 0
+        let spec = spec(r#"[{"id": "a"}, {"id": "b"}, {"id": "c"}]"#, &[&[1.0, 1.0, 0.0], &[1.0, 3.0, 2.0]]);
+        let e = -(0.25 * 0.25f64.ln() + 0.75 * 0.75f64.ln()) / 2f64.ln();
+        let d = [0.0, 1.0 - e, 1.0];
+        let total: f64 = d.iter().sum();
+        let weights = objective_weights(&spec, WeightingMethod::Entropy, None).unwrap();
+        assert_close(&values(&weights), &d.map(|x| x / total));
+    }
+
+    #[test]
+    fn critic_weights() {
+        // нормированные столбцы (0, 1/2, 1) и (1, 1/2, 0): равные σ, r = −1 ⇒ равные веса
+        let spec_conflict = spec(r#"[{"id": "a"}, {"id": "b"}]"#, &[&[1.0, 3.0], &[2.0, 2.0], &[3.0, 1.0]]);
+        let weights = objective_weights(&spec_conflict, WeightingMethod::Critic, None).unwrap();
+        assert_close(&values(&weights), &[0.5, 0.5]);
+        // если b минимизируется, столбцы совпадают: r = 1 и информации для CRITIC нет
+        let spec_same = spec(r#"[{"id": "a"}, {"id": "b", "maximize": false}]"#, &[&[1.0, 3.0], &[2.0, 2.0], &[3.0, 1.0]]);
+        assert!(objective_weights(&spec_same, WeightingMethod::Critic, None).is_err());
+    }
+
+    #[test]
+    fn combined_weights() {
+        let subjective = vec![("a".to_string(), 1.0), ("b".to_string(), 3.0)];
+        let objective = vec![("a".to_string(), 0.5), ("b".to_string(), 0.5)];
+        assert_close(&values(&combine_weights(&subjective, &objective, None)), &[0.25, 0.75]);
+        // 0.5·(1/4, 3/4) + 0.5·(1/2, 1/2)
+        assert_close(&values(&combine_weights(&subjective, &objective, Some(0.5))), &[0.375, 0.625]);
+    }
+}
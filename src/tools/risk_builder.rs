@@ -1,13 +1,90 @@
 // -----------------------------
 // src/tools/risk_builder.rs
 // -----------------------------
-use serde::Serialize;
-use std::{fs, path::PathBuf, process::Command};
+//! Генератор матрицы выплат задачи о продавце газет (newsvendor) для риск-задач.
+//!
+//! Параметры задаются JSON-конфигурацией (`NewsvendorConfig`) и/или флагами командной строки:
+//! уровни спроса (состояния) и объёмы заказа (альтернативы, не обязательно те же), цены закупки
+//! и продажи, остаточная стоимость непроданного, издержки хранения и штраф за дефицит, постоянные
+//! затраты на заказ. Прибыль при заказе q и спросе d:
+//! π(q, d) = c_sell·min(q, d) + (c_salvage − c_hold)·(q − d)⁺ − c_short·(d − q)⁺ − c_buy·q − c_fixed·[q > 0].
+//!
+//! Вероятности спроса задаются числами или оцениваются по истории (`history_estimation`).
+//! Задача сохраняется в указанный файл и сразу решается `RiskSolver` в том же процессе. Без явного
+//! `output` используется data/data_for_risk.json в каталоге проекта; существующий файл по умолчанию
+//! не перезаписывается — задача тогда решается без сохранения.
+use crate::core::base::{DecisionResult, ProblemSpec};
+use crate::core::parser::parse_spec;
+use crate::problems::history_estimation::{estimate_probabilities, read_history, EstimationMethod};
+use crate::problems::risk::RiskSolver;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+
+/// Параметры генератора. В JSON обязательны уровни спроса и цены закупки и продажи, остальные издержки
+/// по умолчанию нулевые; `Default` — исходный пример с пятью уровнями спроса
+#[derive(Debug, Deserialize, Clone)]
+pub struct NewsvendorConfig {
+    /// Уровни спроса — состояния
+    pub demands: Vec<f64>,
+    /// Объёмы заказа — альтернативы (по умолчанию совпадают с уровнями спроса)
+    pub quantities: Option<Vec<f64>>,
+    /// Вероятности уровней спроса
+    pub state_probabilities: Option<Vec<f64>>,
+    /// Файл истории спроса (CSV или JSON), если вероятности нужно оценить
+    pub history: Option<String>,
+    /// Способ оценки по истории: frequency | laplace | poisson | normal | negative-binomial
+    pub estimation: Option<String>,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    /// Цена реализации непроданного остатка
+    #[serde(default)]
+    pub salvage_price: f64,
+    /// Издержки хранения единицы остатка
+    #[serde(default)]
+    pub holding_cost: f64,
+    /// Штраф за единицу неудовлетворённого спроса
+    #[serde(default)]
+    pub shortage_cost: f64,
+    /// Постоянные затраты на размещение ненулевого заказа
+    #[serde(default)]
+    pub fixed_cost: f64,
+    /// Файл, в который сохраняется задача (перезаписывается)
+    pub output: Option<String>,
+}
+
+
+/// Файл по умолчанию — в каталоге проекта, а не в текущем каталоге
+fn default_output() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data").join("data_for_risk.json")
+}
+
+
+impl Default for NewsvendorConfig {
+    fn default() -> Self {
+        NewsvendorConfig {
+            demands: vec![100.0, 150.0, 200.0, 250.0, 300.0],
+            quantities: None,
+            state_probabilities: Some(vec![0.1, 0.3, 0.3, 0.15, 0.15]),
+            history: None,
+            estimation: None,
+            buy_price: 15.0,
+            sell_price: 22.0,
+            salvage_price: 10.0,
+            holding_cost: 0.0,
+            shortage_cost: 0.0,
+            fixed_cost: 0.0,
+            output: None,
+        }
+    }
+}
 
 
 #[derive(Serialize)]
-struct CriterionJson {}
+struct CriterionJson {
+    id: String,
+}
 
 
 #[derive(Serialize)]
@@ -25,70 +102,119 @@ struct RiskSpecJson {
 }
 
 
-pub fn run() -> Result<()> {
-    // Пример: спрос и вероятности
-    let demands = vec![100, 150, 200, 250, 300];
-    let probs = vec![0.1, 0.3, 0.3, 0.15, 0.15];
-
+impl NewsvendorConfig {
+    pub fn read(path: &str) -> Result<Self> {
+        let s = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&s)?)
+    }
 
-    // цены
-    let buy_price = 15.0;
-    let sell_price = 22.0;
-    let salvage_price = 10.0;
+    /// Прибыль при заказе q и спросе d
+    fn profit(&self, q: f64, d: f64) -> f64 {
+        let sold = q.min(d);
+        let leftover = (q - d).max(0.0);
+        let shortage = (d - q).max(0.0);
+        let fixed = if q > 0.0 { self.fixed_cost } else { 0.0 };
+        self.sell_price * sold + (self.salvage_price - self.holding_cost) * leftover
+            - self.shortage_cost * shortage - self.buy_price * q - fixed
+    }
+}
 
 
-    // формируем матрицу выплат (прибылей)
-    let mut alts = Vec::new();
-    for &q in &demands {
-        let mut values = Vec::new();
-        for &n in &demands {
-            let sold = q.min(n) as f64;
-            let leftover = (q as i32 - n as i32).max(0) as f64;
-            let profit = sold * (sell_price - buy_price) + leftover * (salvage_price - buy_price);
-            values.push(profit);
+/// Генерирует задачу по конфигурации, сохраняет её в `config.output` (или в файл по умолчанию, если его ещё нет)
+/// и решает `RiskSolver` с уровнями доверия levels; возвращает задачу (с пояснениями) и результат
+pub fn run(config: &NewsvendorConfig, levels: &[f64]) -> Result<(ProblemSpec, DecisionResult)> {
+    let quantities = config.quantities.clone().unwrap_or_else(|| config.demands.clone());
+    if config.demands.is_empty() || quantities.is_empty() {
+        anyhow::bail!("Нужны хотя бы один уровень спроса и один объём заказа");
+    }
+    if let Some(v) = config.demands.iter().chain(quantities.iter()).find(|v| !v.is_finite() || **v < 0.0) {
+        anyhow::bail!("Спрос и объём заказа должны быть неотрицательными числами: {}", v);
+    }
+    let prices = [config.buy_price, config.sell_price, config.salvage_price, config.holding_cost, config.shortage_cost, config.fixed_cost];
+    if prices.iter().any(|c| !c.is_finite() || *c < 0.0) {
+        anyhow::bail!("Цены и издержки должны быть неотрицательными числами");
+    }
+    let states: Vec<(String, f64)> = config.demands.iter().map(|d| (format!("Спрос {}", d), *d)).collect();
+
+    let mut notes = Vec::new();
+    let probabilities = match (&config.state_probabilities, &config.history) {
+        (Some(_), Some(_)) => anyhow::bail!("Заданы и state_probabilities, и history — оставьте что-то одно"),
+        (Some(probs), None) => probs.clone(),
+        (None, Some(path)) => {
+            let method = EstimationMethod::from_name(config.estimation.as_deref().unwrap_or("laplace"))?;
+            let history = read_history(path)?;
+            let estimate = estimate_probabilities(&history.observations, &states, method, None)?;
+            notes.push(format!("Вероятности спроса оценены по истории {} ({} наблюдений, {})", path, history.observations.len(), method.name()));
+            estimate.probabilities
         }
-        alts.push(AlternativeJson { id: format!("При {}", q), values });
+        (None, None) => anyhow::bail!("Нужны вероятности спроса (state_probabilities) или история спроса (history)"),
+    };
+    if probabilities.len() != config.demands.len() {
+        anyhow::bail!("Вероятностей {}, а уровней спроса {}", probabilities.len(), config.demands.len());
+    }
+    if probabilities.iter().any(|p| *p < 0.0) || (probabilities.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+        anyhow::bail!("Вероятности спроса должны быть неотрицательными и давать в сумме 1");
+    }
+    if config.salvage_price - config.holding_cost > config.buy_price {
+        notes.push("Остаток выгоднее закупки: прибыль растёт с объёмом заказа без ограничений".to_string());
     }
-
 
     let spec = RiskSpecJson {
-        criteria: vec![],
-        state_probabilities: probs,
-        alternatives: alts,
+        criteria: states.iter().map(|(id, _)| CriterionJson { id: id.clone() }).collect(),
+        state_probabilities: probabilities,
+        alternatives: quantities.iter()
+            .map(|&q| AlternativeJson { id: format!("При {}", q), values: config.demands.iter().map(|&d| config.profit(q, d)).collect() })
+            .collect(),
     };
+    let json = serde_json::to_string_pretty(&spec)?;
+    notes.push(format!(
+        "Цены: закупка {}, продажа {}, остаток {}; хранение {}, дефицит {}, постоянные затраты {}",
+        config.buy_price, config.sell_price, config.salvage_price, config.holding_cost, config.shortage_cost, config.fixed_cost
+    ));
+    let target = match &config.output {
+        Some(path) => Some(PathBuf::from(path)),
+        None => {
+            let path = default_output();
+            if path.exists() {
+                notes.push(format!("Файл {} уже существует и не перезаписан; чтобы сохранить задачу, укажите --output", path.display()));
+                None
+            } else {
+                Some(path)
+            }
+        }
+    };
+    if let Some(path) = target {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, &json)?;
+        notes.push(format!("Сгенерирован файл {}", path.display()));
+    }
 
-
-    // путь к файлу внутри projectroot/data
-    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    path.push("data");
-    fs::create_dir_all(&path)?;
-    path.push("data_for_risk.json");
-
-
-    let s = serde_json::to_string_pretty(&spec)?;
-    fs::write(&path, s)?;
-
-
-    println!("Сгенерирован файл {:?}", path);
-
-
-    // Запуск cargo run -- data/data_for_risk.json --method risk
-    let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let rel_path = PathBuf::from("data/data_for_risk.json");
-    let status = Command::new("cargo")
-    .arg("run")
-    .arg("--")
-    .arg(rel_path)
-    .arg("--method")
-    .arg("risk")
-    .current_dir(&project_root)
-    .status()?;
+    let mut spec = parse_spec(&json)?;
+    spec.notes.extend(notes);
+    let result = RiskSolver::solve(&spec, levels)?;
+    Ok((spec, result))
+}
 
 
-    if !status.success() {
-        anyhow::bail!("cargo run failed");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newsvendor_profit() {
+        let mut config = NewsvendorConfig::default();
+        // избыток: 22·150 + 10·50 − 15·200 = 800; дефицит: 22·150 − 15·150 = 1050
+        assert!((config.profit(200.0, 150.0) - 800.0).abs() < 1e-9);
+        assert!((config.profit(150.0, 200.0) - 1050.0).abs() < 1e-9);
+        config.holding_cost = 1.0;
+        config.shortage_cost = 3.0;
+        config.fixed_cost = 200.0;
+        // 22·100 − 3·50 − 15·100 − 200 = 350; без заказа — только штраф 3·150
+        assert!((config.profit(100.0, 150.0) - 350.0).abs() < 1e-9);
+        assert!((config.profit(0.0, 150.0) + 450.0).abs() < 1e-9);
+        // 22·150 + (10 − 1)·50 − 15·200 − 200 = 550
+        assert!((config.profit(200.0, 150.0) - 550.0).abs() < 1e-9);
     }
-
-
-    Ok(())
 }